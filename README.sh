@@ -64,6 +64,36 @@ cargo run -- db --help
 cat <<EOF
 \`\`\`
 
+\`\`\`text
+EOF
+
+echo "$ seella cqlsh --help"
+
+cargo run -- cqlsh --help
+
+cat <<EOF
+\`\`\`
+
+\`\`\`text
+EOF
+
+echo "$ seella archive --help"
+
+cargo run -- archive --help
+
+cat <<EOF
+\`\`\`
+
+\`\`\`text
+EOF
+
+echo "$ seella db export --help"
+
+cargo run -- db export --help
+
+cat <<EOF
+\`\`\`
+
 ## Samples
 
 Run the following:
@@ -90,3 +120,153 @@ You will now have a \`sessions.csv\` and \`events.csv\` in your local directory
 
 Run \`make down\` when you're done to shut down the cluster.
 EOF
+
+cat <<EOF
+
+If all you have is the tracing table that cqlsh prints after each query, save it to a file (or pipe it in) and use the
+\`cqlsh\` mode instead:
+
+\`\`\`shell
+seella cqlsh trace.txt
+pbpaste | seella cqlsh
+\`\`\`
+
+Traces in \`system_traces\` expire after 24 hours by default. To keep them around, or to share them without the pair of
+CSVs, export them into a single archive and read them back later:
+
+\`\`\`shell
+seella db export -o traces.jsonl.zst 74ff67c0-397b-11ee-8ca4-9688db6cc0f1 74612bf0-397b-11ee-8ca4-9688db6cc0f1
+seella archive -p traces.jsonl.zst 74ff67c0-397b-11ee-8ca4-9688db6cc0f1
+\`\`\`
+
+Add \`--redact\` before the mode to replace the literal values in the query, IP addresses, tokens and usernames before
+posting a trace publicly. Each node is given a consistent address from \`192.0.2.0/24\`, so the shape of the trace is
+kept. This also applies to \`seella --redact db export\`.
+
+For long traces, \`seella tui\` followed by any of the other modes browses the session interactively instead, with
+collapsible subtrees, search, and a waterfall that can be zoomed into:
+
+\`\`\`shell
+seella tui csv 75007930-397b-11ee-8ca4-9688db6cc0f1
+\`\`\`
+
+Range scans read from every shard, and so produce hundreds of near-identical events. Add \`--group\` to merge runs of
+events on the same node that only differ by their numbers, addresses and tokens into a single row, showing how many
+there were, their total duration, and the slowest. A group keeps the number of its first event, which can be given to
+\`--expand\` to show its events separately again:
+
+\`\`\`shell
+seella --group --expand 7 csv 75007930-397b-11ee-8ca4-9688db6cc0f1
+\`\`\`
+
+To find out which replica a query was actually waiting on, add \`--critical-path\`. Spans started by the same event,
+such as the request being handled on each replica, run alongside each other, so only the slowest of them holds up the
+query. The chain of events through the slowest span at each level is drawn as normal, and every other event is shaded
+out.
+
+Add \`--summary\` to follow the chart with the events summed up by node, and by shard of each node, showing how many
+ran there, their total self time, when the first and last of them started, and their share of the self time of every
+event. This quickly shows whether a slow query was held up by the coordinator or by a single shard of one replica.
+
+Add \`--replicas\` to follow the chart with the replicas that the coordinator sent requests to, pieced together from
+activities such as \`Sending a mutation to /172.17.0.3\` and \`Got a response from /172.17.0.3\`. Each request is listed
+with when it was sent, when the response arrived, and the round trip between them, along with the natural, pending,
+live and dead replicas chosen for each write.
+
+Reports such as \`--summary\` and \`--replicas\` can also be written as JSON, without the chart, for use in other tools:
+
+\`\`\`shell
+seella --replicas --format json csv 74ff67c0-397b-11ee-8ca4-9688db6cc0f1 | jq '.replicas.requests'
+\`\`\`
+
+Add \`--reads\` to follow the chart with what each replica read: partitions, live rows, tombstones, the sstables read
+from, row cache lookups and misses, digest mismatches and read repairs. A warning is shown for any replica where more
+than half of the rows read were tombstones, or that read from more than 10 sstables. Both limits can be changed with
+\`--max-tombstone-ratio\` and \`--max-sstables\`.
+
+For lightweight transactions, add \`--paxos\` to group the events into their prepare, read, accept, learn and prune
+phases. Each phase is drawn on its own timeline, with how many events and nodes took part, followed by a warning if it
+took more than one ballot, and any activities showing contention with another transaction, such as a rejected promise.
+
+Add \`--lint\` to follow the chart with anything unusual about the trace, each pointing at the number of the row it was
+found on: gaps of a millisecond or more between two events of the same span (see \`--max-gap\`), time going backwards
+within a span, throttling, speculative retries, timeouts, dead replicas and writes forwarded to another datacenter.
+With \`--format json\` the findings are written out as a \`lint\` array instead.
+
+To compare two traces of the same query, such as a fast run against a slow one, use \`diff\` with where to load them
+both from, followed by the session before and the session after. Events are lined up by their activity, with any
+numbers, tokens or addresses ignored, and by where they are in the tree. Each is shown with its duration in both
+sessions and the difference, with \`+\` marking events that only the second session has and \`-\` those that it's
+missing:
+
+\`\`\`shell
+seella diff csv 74ff67c0-397b-11ee-8ca4-9688db6cc0f1 7d4f6b50-397b-11ee-8ca4-9688db6cc0f1
+\`\`\`
+
+cqlsh output only holds one session, so give the path to a second file of tracing output with \`--other-path\`
+instead:
+
+\`\`\`shell
+seella diff cqlsh fast.txt --other-path slow.txt
+\`\`\`
+
+To see how a query performs across many sessions, such as those sampled with \`nodetool settraceprobability\`, use
+\`stats\` with every session in a CSV export or archive, or those started within a window of time in a live database.
+Sessions are grouped by their query, with literal values ignored, and each query is shown with the 50th, 90th and 99th
+percentiles and maximum of the session duration, followed by the same for the time spent on each activity:
+
+\`\`\`shell
+seella stats csv -s sessions.csv -e events.csv
+seella stats db --since 1h
+\`\`\`
+
+\`--since\` and \`--until\` take either an RFC 3339 timestamp such as \`2023-08-13T01:48:00Z\`, or a time ago such as \`30m\`,
+\`2h\` or \`1d\`.
+
+To see what a typical run of a query looks like, use \`profile\` with the same sources as \`stats\`. The sessions of each
+query are merged into a single tree, matching events up by their activity and where they are in the tree, and drawn
+as a waterfall chart using the mean duration of each event. Each event also shows its 99th percentile duration and the
+share of sessions it was seen in, so that events which only happen in the outliers stand out. Use \`--query\` to only
+profile queries matching a regular expression:
+
+\`\`\`shell
+seella profile --query '^SELECT' csv -s sessions.csv -e events.csv
+\`\`\`
+
+To find the slowest sessions, use \`top\` with the same sources as \`stats\`. Sessions are ranked by their \`duration\`,
+and the slowest are listed before each is shown in full, one after another. Use \`-n\` to change how many are shown, and
+\`--list\` to only list them. Sessions can be narrowed down with \`--coordinator\`, \`--client\`, \`--command\`,
+\`--username\`, \`--keyspace\` or \`--table\`, which are matched against the names used in the query,
+\`--min-session-duration\`, and \`--query\`, a regular expression matched against the query with its values replaced:
+
+\`\`\`shell
+seella top -n 5 --table users db --since 1h
+\`\`\`
+
+Scylla's slow query log, \`system_traces.node_slow_log\`, can be listed with \`slow-log\`, either from a live database
+with the same \`--since\` and \`--until\` window as \`stats\`, or from a CSV export of the table alongside the sessions
+and events CSVs. Use \`--show\` with an entry's number in the list, its session id, or its start time to render the
+session it was traced in, as if it had been asked for directly:
+
+\`\`\`shell
+seella slow-log db --since 1h
+seella slow-log --show 3 db --since 1h
+seella slow-log --show 7d4f6b50-397b-11ee-8ca4-9688db6cc0f1 csv -l node_slow_log.csv -s sessions.csv -e events.csv
+\`\`\`
+
+To watch traces as they come in, such as during a load test with probabilistic tracing turned on, use \`db follow\`.
+Every couple of seconds it looks for new sessions, and shows each one in full as it's written, like \`tail -f\`. It
+takes the same filters as \`top\`, and \`--slow-log\` only follows the sessions written to the slow query log:
+
+\`\`\`shell
+seella db follow --min-session-duration 10000 --query '^SELECT'
+\`\`\`
+
+Every \`db\` command reads at consistency \`one\` by default. \`--consistency\` asks for another level, \`--timeout\`
+sets how many seconds to wait for each request, and \`--page-size\` sets how many rows come back at a time, which helps
+with sessions that have very many events:
+
+\`\`\`shell
+seella db --consistency local_quorum --timeout 30 --page-size 500 dc48a4b0-2fb6-11ee-9a49-c3b3a7e0d4c1
+\`\`\`
+EOF
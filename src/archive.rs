@@ -0,0 +1,131 @@
+use crate::{
+    input::{self, Output},
+    records::{EventRecord, SessionRecord},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A single session and all of its events, as stored in a seella archive.
+///
+/// An archive is a JSON-lines file with one of these per line, so archives can be concatenated with `cat`, and may be
+/// gzip or zstd compressed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    pub session: SessionRecord,
+    pub events: Vec<EventRecord>,
+}
+
+/// A source for the data based on a seella archive, as written by `seella db export`.
+#[derive(Debug)]
+pub struct ArchiveSource<'a> {
+    path: &'a PathBuf,
+    session_id: Option<Uuid>,
+}
+
+impl<'a> ArchiveSource<'a> {
+    /// If no session id is given, the archive must contain exactly one session.
+    pub fn new(path: &'a PathBuf, session_id: Option<Uuid>) -> Self {
+        Self { path, session_id }
+    }
+}
+
+/// The kinds of errors that can be experienced while reading or writing an archive.
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error("the provided session id {0} could not be found")]
+    SessionNotFound(Uuid),
+    #[error("the archive does not contain any sessions")]
+    Empty,
+    #[error("the archive contains {0} sessions, please provide a session id")]
+    AmbiguousSession(usize),
+    #[error("line {line}: could not parse the archived session: {source}")]
+    Json {
+        line: usize,
+        source: serde_json::Error,
+    },
+    #[error("could not write the archived session: {0}")]
+    Serialize(serde_json::Error),
+    #[error("there were issues reading or writing the archive")]
+    IoError(#[from] std::io::Error),
+}
+
+impl<'a> ArchiveSource<'a> {
+    pub fn get_data(&self) -> Result<(SessionRecord, Vec<EventRecord>), ArchiveError> {
+        let mut found = None;
+        let mut count = 0;
+
+        for archived in self.sessions()? {
+            let archived = archived?;
+            count += 1;
+
+            match self.session_id {
+                Some(session_id) if archived.session.session_id == session_id => {
+                    return Ok((archived.session, archived.events));
+                }
+                Some(_) => {}
+                None => found = Some(archived),
+            }
+        }
+
+        match (self.session_id, found) {
+            (Some(session_id), _) => Err(ArchiveError::SessionNotFound(session_id)),
+            (None, None) => Err(ArchiveError::Empty),
+            (None, Some(archived)) if count == 1 => Ok((archived.session, archived.events)),
+            (None, _) => Err(ArchiveError::AmbiguousSession(count)),
+        }
+    }
+
+    /// Iterates over every session in the archive, in the order they were written.
+    pub fn sessions(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<ArchivedSession, ArchiveError>>, ArchiveError> {
+        let lines = input::open(self.path)?.lines();
+
+        Ok(lines
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .map(|(i, line)| {
+                serde_json::from_str(&line?).map_err(|source| ArchiveError::Json {
+                    line: i + 1,
+                    source,
+                })
+            }))
+    }
+}
+
+/// Writes sessions to a seella archive, one per line.
+pub struct ArchiveWriter {
+    output: Output,
+}
+
+impl ArchiveWriter {
+    /// Creates the archive at the given path, or writes to stdout if the path is `-`. Compressed if the path ends in
+    /// `.gz` or `.zst`.
+    pub fn create(path: &Path) -> Result<Self, ArchiveError> {
+        Ok(Self {
+            output: input::create(path)?,
+        })
+    }
+
+    pub fn write(
+        &mut self,
+        session: SessionRecord,
+        events: Vec<EventRecord>,
+    ) -> Result<(), ArchiveError> {
+        serde_json::to_writer(&mut self.output, &ArchivedSession { session, events })
+            .map_err(ArchiveError::Serialize)?;
+        self.output.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Writes out the rest of the archive, so that a compressed archive isn't left truncated.
+    pub fn finish(self) -> Result<(), ArchiveError> {
+        self.output.finish()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    color::{self, BOLD},
+    event::format_duration,
+    Cli, Session,
+};
+use serde::Serialize;
+use std::net::IpAddr;
+
+/// The events of a session that ran on one node, or on one shard of a node, summed up; see [Session::breakdown].
+///
+/// Offsets are in microseconds from the start of the session, as laid out in the waterfall chart.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Breakdown {
+    pub source: IpAddr,
+    /// The shard, or `None` when summing up the whole node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<String>,
+    /// The number of events
+    pub events: usize,
+    /// The durations of the events, not including any children that ran elsewhere
+    pub self_time: i64,
+    /// The offset of the first event
+    pub first_offset: i64,
+    /// The offset of the last event
+    pub last_offset: i64,
+}
+
+impl Session {
+    /// Sums up the events by the node that they ran on, or by shard if `per_shard` is set, in the order that each was
+    /// first seen.
+    ///
+    /// Shows whether a slow query was held up by the coordinator, or by one of the replicas.
+    pub fn breakdown(&self, per_shard: bool) -> Vec<Breakdown> {
+        let mut breakdowns: Vec<Breakdown> = Vec::new();
+        let mut offset = 0;
+
+        for (event, _) in self.events() {
+            let thread = per_shard.then(|| event.thread.clone());
+            let self_time = event.durations().1;
+
+            match breakdowns
+                .iter_mut()
+                .find(|b| b.source == event.source && b.thread == thread)
+            {
+                Some(breakdown) => {
+                    breakdown.events += 1;
+                    breakdown.self_time += self_time;
+                    breakdown.last_offset = offset;
+                }
+                None => breakdowns.push(Breakdown {
+                    source: event.source,
+                    thread,
+                    events: 1,
+                    self_time,
+                    first_offset: offset,
+                    last_offset: offset,
+                }),
+            }
+
+            offset += self_time;
+        }
+
+        breakdowns
+    }
+
+    /// Writes the per-node and per-shard summary tables shown after the chart with `--summary`.
+    pub(crate) fn display_breakdown(
+        &self,
+        cli: &Cli,
+        w: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let color = cli.color.enabled();
+        // The share of the self time of every event, which adds up to the whole chart. The session's own duration
+        // isn't used, as the nodes' events overlap and their self times can add up to more than it. Guard against
+        // dividing by zero for sessions without any recorded time
+        let total = self.total_duration().max(1) as f64;
+
+        for per_shard in [false, true] {
+            let breakdowns = self.breakdown(per_shard);
+            let shard_width = breakdowns
+                .iter()
+                .filter_map(|b| b.thread.as_ref().map(|thread| thread.len()))
+                .chain(std::iter::once("shard".len()))
+                .max()
+                .unwrap_or(0);
+            let row = |node: &str, shard: &str, rest: [&str; 5]| match per_shard {
+                true => format!(
+                    "{node:15} {shard:shard_width$} {:>6} {:>10} {:>10} {:>10} {:>6}",
+                    rest[0], rest[1], rest[2], rest[3], rest[4]
+                ),
+                false => format!(
+                    "{node:15} {:>6} {:>10} {:>10} {:>10} {:>6}",
+                    rest[0], rest[1], rest[2], rest[3], rest[4]
+                ),
+            };
+
+            writeln!(w)?;
+            let headers = row(
+                "node",
+                "shard",
+                ["events", "self time", "first", "last", "self %"],
+            );
+            writeln!(w, "{}", color::paint(headers.trim_end(), BOLD, color))?;
+
+            for b in &breakdowns {
+                writeln!(
+                    w,
+                    "{}",
+                    row(
+                        &b.source.to_string(),
+                        b.thread.as_deref().unwrap_or_default(),
+                        [
+                            &b.events.to_string(),
+                            &format_duration(cli, b.self_time),
+                            &format_duration(cli, b.first_offset),
+                            &format_duration(cli, b.last_offset),
+                            &format!("{:.1}%", b.self_time as f64 * 100.0 / total),
+                        ]
+                    )
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use clap::Args;
+use std::{ffi::OsString, fmt::Display, ops::Deref, path::PathBuf};
+
+/// Options that are specific to the archive mode of operation.
+#[derive(Debug, Args, Clone, Default)]
+pub struct ArchiveModeOptions {
+    /// The session id to be visualised. May be left out if the archive only contains a single session
+    pub session_id: Option<String>,
+
+    /// Path to the archive written by `seella db export`, or `-` for stdin. May be gzip or zstd compressed
+    #[arg(short, long, default_value_t)]
+    pub path: ArchivePath,
+}
+
+/// Default path to a seella archive.
+///
+/// Type to provide a correct `Default::default()` PathBuf for clap.
+#[derive(Debug, Clone)]
+pub struct ArchivePath(pub PathBuf);
+
+impl Default for ArchivePath {
+    fn default() -> Self {
+        Self(PathBuf::from("traces.jsonl"))
+    }
+}
+
+impl Display for ArchivePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+impl From<OsString> for ArchivePath {
+    fn from(value: OsString) -> Self {
+        Self(PathBuf::from(value))
+    }
+}
+
+impl Deref for ArchivePath {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
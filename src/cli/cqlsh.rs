@@ -0,0 +1,42 @@
+use clap::Args;
+use std::{ffi::OsString, fmt::Display, ops::Deref, path::PathBuf};
+
+/// Options that are specific to the cqlsh mode of operation.
+#[derive(Debug, Args, Clone, Default)]
+pub struct CqlshModeOptions {
+    /// Path to a file containing the tracing output of cqlsh, or `-` to read from stdin
+    #[arg(default_value_t)]
+    pub path: CqlshPath,
+}
+
+/// Default path to the cqlsh tracing output.
+///
+/// Type to provide a correct `Default::default()` PathBuf for clap.
+#[derive(Debug, Clone)]
+pub struct CqlshPath(pub PathBuf);
+
+impl Default for CqlshPath {
+    fn default() -> Self {
+        Self(PathBuf::from("-"))
+    }
+}
+
+impl Display for CqlshPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+impl From<OsString> for CqlshPath {
+    fn from(value: OsString) -> Self {
+        Self(PathBuf::from(value))
+    }
+}
+
+impl Deref for CqlshPath {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -1,5 +1,6 @@
 use clap::Args;
-use std::{ffi::OsString, fmt::Display, ops::Deref, path::PathBuf};
+use std::{ffi::OsString, fmt::Display, ops::Deref, path::PathBuf, str::FromStr};
+use thiserror::Error;
 
 /// Options that are specific to the CSV mode of operation.
 #[derive(Debug, Args, Clone, Default)]
@@ -7,13 +8,61 @@ pub struct CsvModeOptions {
     /// The session id to be visualised
     pub session_id: String,
 
-    /// Path to the CSV containing the sessions data. Any string that can be coerced into a PathBuf
+    /// Path to the CSV containing the sessions data, or `-` for stdin. May be gzip or zstd compressed
     #[arg(short, long, default_value_t)]
     pub sessions_path: SessionsPath,
 
-    /// Path to the CSV containing the events data. Any string that can be coerced into a PathBuf
+    /// Path to the CSV containing the events data, or `-` for stdin. May be gzip or zstd compressed
     #[arg(short, long, default_value_t)]
     pub events_path: EventsPath,
+
+    /// Path to a directory or tarball containing both a sessions.csv and events.csv, or `-` for a tarball on stdin.
+    /// Used instead of the sessions and events paths
+    #[arg(short, long, conflicts_with_all = ["sessions_path", "events_path"])]
+    pub bundle: Option<PathBuf>,
+
+    /// Build (on first use) and use an index of where each session lies within the events CSV, saved alongside it.
+    /// Speeds up repeated lookups in large, uncompressed exports
+    #[arg(short, long)]
+    pub index: bool,
+
+    /// Read the whole events CSV rather than stopping after the session's rows. Only needed when a session's rows are
+    /// not contiguous, such as when several exports have been concatenated
+    #[arg(long)]
+    pub full_scan: bool,
+
+    /// Skip rows that can't be read, printing a warning for each, rather than failing
+    #[arg(long)]
+    pub lenient: bool,
+
+    #[command(flatten)]
+    pub format: CsvFormatOptions,
+}
+
+/// The options that were given to `COPY TO` when exporting the CSVs.
+#[derive(Debug, Args, Clone, Default)]
+pub struct CsvFormatOptions {
+    /// The delimiter the CSVs were exported with, i.e. `COPY ... WITH DELIMITER = '|'`
+    #[arg(long, default_value_t)]
+    pub delimiter: Delimiter,
+
+    /// The quote character the CSVs were exported with, i.e. `COPY ... WITH QUOTE = '''`
+    #[arg(long, default_value_t)]
+    pub quote: Quote,
+
+    /// The escape character the CSVs were exported with, i.e. `COPY ... WITH ESCAPE = '\'`. By default quotes are
+    /// escaped by doubling them
+    #[arg(long)]
+    pub escape: Option<Escape>,
+
+    /// The CSVs have no header row, i.e. they were exported with `COPY ... WITH HEADER = FALSE`. The default column
+    /// order of `COPY TO` is assumed
+    #[arg(long)]
+    pub no_header: bool,
+
+    /// The string the CSVs were exported with for null values, i.e. `COPY ... WITH NULL = 'null'`
+    #[arg(long)]
+    pub null: Option<String>,
 }
 
 /// Default path to the [Session][crate::SessionRecord] source.
@@ -79,3 +128,105 @@ impl Deref for EventsPath {
         &self.0
     }
 }
+
+/// The character given for an option like `--delimiter` was not a single ASCII character.
+#[derive(Debug, Error)]
+#[error("expected a single ASCII character")]
+pub struct NotAsciiChar;
+
+fn parse_ascii_char(s: &str) -> Result<u8, NotAsciiChar> {
+    match s.as_bytes() {
+        [byte] if byte.is_ascii() => Ok(*byte),
+        _ => Err(NotAsciiChar),
+    }
+}
+
+/// Default delimiter for the exported CSVs.
+///
+/// Type to provide a correct `Default::default()` u8 for clap.
+#[derive(Debug, Clone)]
+pub struct Delimiter(pub u8);
+
+impl Default for Delimiter {
+    fn default() -> Self {
+        Self(b',')
+    }
+}
+
+impl Display for Delimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 as char)
+    }
+}
+
+impl FromStr for Delimiter {
+    type Err = NotAsciiChar;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_ascii_char(s)?))
+    }
+}
+
+impl Deref for Delimiter {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Default quote character for the exported CSVs.
+///
+/// Type to provide a correct `Default::default()` u8 for clap.
+#[derive(Debug, Clone)]
+pub struct Quote(pub u8);
+
+impl Default for Quote {
+    fn default() -> Self {
+        Self(b'"')
+    }
+}
+
+impl Display for Quote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 as char)
+    }
+}
+
+impl FromStr for Quote {
+    type Err = NotAsciiChar;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_ascii_char(s)?))
+    }
+}
+
+impl Deref for Quote {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Escape character for the exported CSVs.
+///
+/// Type to parse a single ASCII character into a u8 for clap.
+#[derive(Debug, Clone)]
+pub struct Escape(pub u8);
+
+impl FromStr for Escape {
+    type Err = NotAsciiChar;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_ascii_char(s)?))
+    }
+}
+
+impl Deref for Escape {
+    type Target = u8;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -1,20 +1,88 @@
-use clap::Args;
+use super::{ArchivePath, SessionFilterOptions};
+use crate::DEFAULT_PAGE_SIZE;
+use clap::{Args, Subcommand};
+use scylla::statement::Consistency;
 use std::{
     fmt::Display,
     net::{AddrParseError, Ipv4Addr, SocketAddr, SocketAddrV4},
+    num::ParseIntError,
     ops::Deref,
     str::FromStr,
 };
+use thiserror::Error;
 
 /// Options that are specific to the DB mode of operation.
 #[derive(Debug, Args, Clone, Default)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct DbModeOptions {
     /// The session id to be visualised.
-    pub session_id: String,
+    #[arg(required = true)]
+    pub session_id: Option<String>,
 
     /// Socket Address (IP address and port) for the database connection. See [std::net::SocketAddr::from_str].
-    #[arg(short, long, default_value_t)]
+    #[arg(short, long, default_value_t, global = true)]
     pub addr: DbAddr,
+
+    #[command(flatten)]
+    pub driver: DbDriverOptions,
+
+    /// Something other than visualising a single session
+    #[command(subcommand)]
+    pub action: Option<DbAction>,
+}
+
+/// Options for how requests are made to the database.
+#[derive(Debug, Args, Clone, Default)]
+pub struct DbDriverOptions {
+    /// Consistency level for every request: any, one, two, three, quorum, all, local_quorum, each_quorum or
+    /// local_one
+    #[arg(long, default_value_t, global = true)]
+    pub consistency: DbConsistency,
+
+    /// How long to wait for each request, and for each connection to be made, in seconds. The driver's defaults are
+    /// used if not set
+    #[arg(long, global = true)]
+    pub timeout: Option<u64>,
+
+    /// How many rows to fetch at a time, such as the events of a session with very many of them
+    #[arg(long, default_value_t, global = true)]
+    pub page_size: PageSize,
+}
+
+/// Actions against the database other than visualising a single session.
+#[derive(Debug, Subcommand, Clone)]
+pub enum DbAction {
+    /// Save sessions and their events into a seella archive, to be read later with `seella archive`
+    Export(ExportOptions),
+    /// Show each new session as it's written, like `tail -f`, until interrupted
+    Follow(FollowOptions),
+}
+
+/// Options for exporting sessions into a seella archive.
+#[derive(Debug, Args, Clone, Default)]
+pub struct ExportOptions {
+    /// The session ids to be exported
+    #[arg(required = true)]
+    pub session_ids: Vec<String>,
+
+    /// Path to write the archive to, or `-` for stdout. Compressed if it ends in `.gz` or `.zst`
+    #[arg(short, long, default_value_t)]
+    pub output: ArchivePath,
+}
+
+/// Options for following new sessions as they're written.
+#[derive(Debug, Args, Clone, Default)]
+pub struct FollowOptions {
+    /// How often to check for new sessions, in seconds
+    #[arg(long, default_value_t)]
+    pub interval: PollInterval,
+
+    /// Only follow the sessions written to the slow query log
+    #[arg(long)]
+    pub slow_log: bool,
+
+    #[command(flatten)]
+    pub filter: SessionFilterOptions,
 }
 
 /// Default SocketAddr for connection to a database.
@@ -53,3 +121,144 @@ impl Deref for DbAddr {
         &self.0
     }
 }
+
+/// Default number of seconds between each check for new sessions.
+///
+/// Type to provide a correct `Default::default()` u64 for clap.
+#[derive(Debug, Clone)]
+pub struct PollInterval(pub u64);
+
+impl Default for PollInterval {
+    fn default() -> Self {
+        Self(2)
+    }
+}
+
+impl Display for PollInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for PollInterval {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(u64::from_str(s)?))
+    }
+}
+
+impl Deref for PollInterval {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Default consistency level for requests to the database.
+///
+/// Type to provide a correct `Default::default()` Consistency for clap.
+#[derive(Debug, Clone)]
+pub struct DbConsistency(pub Consistency);
+
+impl Default for DbConsistency {
+    fn default() -> Self {
+        Self(Consistency::One)
+    }
+}
+
+impl Display for DbConsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.0 {
+            Consistency::Any => "any",
+            Consistency::One => "one",
+            Consistency::Two => "two",
+            Consistency::Three => "three",
+            Consistency::Quorum => "quorum",
+            Consistency::All => "all",
+            Consistency::LocalQuorum => "local_quorum",
+            Consistency::EachQuorum => "each_quorum",
+            Consistency::LocalOne => "local_one",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The level given for `--consistency` is not one we know of.
+#[derive(Debug, Error)]
+#[error(
+    "expected one of any, one, two, three, quorum, all, local_quorum, each_quorum or local_one"
+)]
+pub struct InvalidConsistency;
+
+impl FromStr for DbConsistency {
+    type Err = InvalidConsistency;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let consistency = match s.to_ascii_lowercase().replace('-', "_").as_str() {
+            "any" => Consistency::Any,
+            "one" => Consistency::One,
+            "two" => Consistency::Two,
+            "three" => Consistency::Three,
+            "quorum" => Consistency::Quorum,
+            "all" => Consistency::All,
+            "local_quorum" => Consistency::LocalQuorum,
+            "each_quorum" => Consistency::EachQuorum,
+            "local_one" => Consistency::LocalOne,
+            _ => return Err(InvalidConsistency),
+        };
+
+        Ok(Self(consistency))
+    }
+}
+
+impl Deref for DbConsistency {
+    type Target = Consistency;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Default number of rows to fetch at a time from the database.
+///
+/// Type to provide a correct `Default::default()` i32 for clap.
+#[derive(Debug, Clone)]
+pub struct PageSize(pub i32);
+
+impl Default for PageSize {
+    fn default() -> Self {
+        Self(DEFAULT_PAGE_SIZE)
+    }
+}
+
+impl Display for PageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The number given for `--page-size` was not a positive whole number.
+#[derive(Debug, Error)]
+#[error("expected a number of rows greater than zero")]
+pub struct InvalidPageSize;
+
+impl FromStr for PageSize {
+    type Err = InvalidPageSize;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match i32::from_str(s) {
+            Ok(page_size) if page_size > 0 => Ok(Self(page_size)),
+            _ => Err(InvalidPageSize),
+        }
+    }
+}
+
+impl Deref for PageSize {
+    type Target = i32;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,112 @@
+use super::{
+    ArchiveModeOptions, CqlshModeOptions, CqlshPath, CsvModeOptions, DbAddr, DbDriverOptions,
+    DbModeOptions, SessionSource,
+};
+use clap::{Args, Subcommand};
+
+/// Options that are specific to the diff mode of operation.
+#[derive(Debug, Args, Clone)]
+pub struct DiffModeOptions {
+    /// Where to load the sessions from
+    #[command(subcommand)]
+    pub source: DiffSource,
+}
+
+/// The data sources that a pair of sessions can be loaded from; the same as the other modes of operation, but with
+/// the session to compare against following the first.
+#[derive(Debug, Subcommand, Clone)]
+pub enum DiffSource {
+    /// Use a pair of CSVs as a data source
+    #[command(mut_arg("session_id", before))]
+    Csv {
+        #[command(flatten)]
+        options: CsvModeOptions,
+        #[arg(index = 2, value_name = "AFTER", help = AFTER_HELP)]
+        after: String,
+    },
+    /// Use a live database as a data source
+    Db {
+        #[arg(value_name = "BEFORE", help = BEFORE_HELP)]
+        before: String,
+        #[arg(value_name = "AFTER", help = AFTER_HELP)]
+        after: String,
+
+        /// Socket Address (IP address and port) for the database connection. See [std::net::SocketAddr::from_str].
+        #[arg(short, long, default_value_t)]
+        addr: DbAddr,
+
+        #[command(flatten)]
+        driver: DbDriverOptions,
+    },
+    /// Use the tracing output printed by cqlsh as a data source
+    Cqlsh {
+        #[command(flatten)]
+        options: CqlshModeOptions,
+        /// Path to a second file of tracing output to compare against, as the output of cqlsh only holds one session
+        #[arg(long)]
+        other_path: CqlshPath,
+    },
+    /// Use a seella archive, as written by `seella db export`, as a data source
+    #[command(mut_arg("session_id", |arg| before(arg).required(true)))]
+    Archive {
+        #[command(flatten)]
+        options: ArchiveModeOptions,
+        #[arg(index = 2, value_name = "AFTER", help = AFTER_HELP)]
+        after: String,
+    },
+}
+
+const BEFORE_HELP: &str = "The session to compare from, i.e. before";
+const AFTER_HELP: &str = "The session to compare against it, i.e. after";
+
+/// Describes the session id of a data source as the session before.
+fn before(arg: clap::Arg) -> clap::Arg {
+    arg.value_name("BEFORE").help(BEFORE_HELP)
+}
+
+impl DiffSource {
+    /// Where to load the sessions before and after from.
+    pub fn sources(&self) -> (SessionSource, SessionSource) {
+        match self {
+            DiffSource::Csv { options, after } => (
+                SessionSource::Csv(options.clone()),
+                SessionSource::Csv(CsvModeOptions {
+                    session_id: after.clone(),
+                    ..options.clone()
+                }),
+            ),
+            DiffSource::Db {
+                before,
+                after,
+                addr,
+                driver,
+            } => {
+                let db = |session_id: &String| {
+                    SessionSource::Db(DbModeOptions {
+                        session_id: Some(session_id.clone()),
+                        addr: addr.clone(),
+                        driver: driver.clone(),
+                        action: None,
+                    })
+                };
+                (db(before), db(after))
+            }
+            DiffSource::Cqlsh {
+                options,
+                other_path,
+            } => (
+                SessionSource::Cqlsh(options.clone()),
+                SessionSource::Cqlsh(CqlshModeOptions {
+                    path: other_path.clone(),
+                }),
+            ),
+            DiffSource::Archive { options, after } => (
+                SessionSource::Archive(options.clone()),
+                SessionSource::Archive(ArchiveModeOptions {
+                    session_id: Some(after.clone()),
+                    ..options.clone()
+                }),
+            ),
+        }
+    }
+}
@@ -1,11 +1,47 @@
 use clap::{Parser, Subcommand, ValueEnum};
-use std::{fmt::Display, num::ParseIntError, ops::Deref, str::FromStr};
-
+use regex::Regex;
+use std::{
+    fmt::Display,
+    net::IpAddr,
+    num::{ParseFloatError, ParseIntError},
+    ops::Deref,
+    str::FromStr,
+};
+
+mod archive;
+mod cqlsh;
 mod csv;
 mod db;
-
-pub use csv::{CsvModeOptions, EventsPath, SessionsPath};
-pub use db::{DbAddr, DbModeOptions};
+mod diff;
+mod profile;
+mod session;
+mod sessions;
+mod slow_log;
+mod stats;
+mod top;
+mod tui;
+
+pub use archive::{ArchiveModeOptions, ArchivePath};
+pub use cqlsh::{CqlshModeOptions, CqlshPath};
+pub use csv::{
+    CsvFormatOptions, CsvModeOptions, Delimiter, Escape, EventsPath, NotAsciiChar, Quote,
+    SessionsPath,
+};
+pub use db::{
+    DbAction, DbAddr, DbConsistency, DbDriverOptions, DbModeOptions, ExportOptions, FollowOptions,
+    InvalidConsistency, InvalidPageSize, PageSize, PollInterval,
+};
+pub use diff::{DiffModeOptions, DiffSource};
+pub use profile::ProfileModeOptions;
+pub use session::SessionSource;
+pub use sessions::{
+    ArchiveSessionsOptions, CsvSessionsOptions, DbSessionsOptions, InvalidTimestamp,
+    SessionFilterOptions, SessionsSource, Timestamp,
+};
+pub use slow_log::{CsvSlowLogOptions, SlowLogModeOptions, SlowLogPath, SlowLogSource};
+pub use stats::StatsModeOptions;
+pub use top::{TopCount, TopModeOptions};
+pub use tui::TuiModeOptions;
 
 //   ___ _    ___
 //  / __| |  |_ _|
@@ -13,7 +49,7 @@ pub use db::{DbAddr, DbModeOptions};
 //  \___|____|___|
 
 /// Configuration for the cli.
-#[derive(Debug, Parser, Default)]
+#[derive(Debug, Parser, Default, Clone)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     /// The mode of operation to use, and data source to use
@@ -36,6 +72,12 @@ pub struct Cli {
     #[arg(long, default_value_t)]
     pub max_activity_width: MaxActivityWidth,
 
+    /// Fit the chart to the width of the terminal, choosing the waterfall and activity widths to suit, and marking
+    /// activities that are cut short with an ellipsis. Falls back to `--waterfall-width` and `--max-activity-width`
+    /// when not writing to a terminal
+    #[arg(long)]
+    pub auto_width: bool,
+
     /// Whether to show the event uuid
     #[arg(long)]
     pub show_event_id: bool,
@@ -47,6 +89,94 @@ pub struct Cli {
     /// Whether to show the thread name
     #[arg(long)]
     pub show_thread: bool,
+
+    /// Only show events from this node, along with their ancestors. May be given more than once
+    #[arg(long)]
+    pub node: Vec<IpAddr>,
+
+    /// Only show events from this thread, i.e. `shard 3`, along with their ancestors. May be given more than once
+    #[arg(long)]
+    pub thread: Vec<String>,
+
+    /// Only show events with an activity matching this regular expression, along with their ancestors
+    #[arg(long)]
+    pub activity: Option<Regex>,
+
+    /// Only show events that took at least this many microseconds, along with their ancestors
+    #[arg(long)]
+    pub min_duration: Option<i64>,
+
+    /// Only show events at most this deep in the tree, where the root events are at depth 0
+    #[arg(long)]
+    pub max_depth: Option<usize>,
+
+    /// Merge runs of similar events, such as those from a range scan, into a single row showing how many there were
+    #[arg(long)]
+    pub group: bool,
+
+    /// Show the events of the group with this number separately. May be given more than once
+    #[arg(long, requires = "group")]
+    pub expand: Vec<usize>,
+
+    /// When to colour the output
+    #[arg(value_enum, long, default_value_t)]
+    pub color: ColorChoice,
+
+    /// Whether to colour the waterfall bars by the node or by the shard that the event came from
+    #[arg(value_enum, long, default_value_t)]
+    pub color_by: ColorBy,
+
+    /// Highlight events that took at least this many microseconds
+    #[arg(long)]
+    pub slow_threshold: Option<i64>,
+
+    /// After the chart, sum up the events by node and by shard, to show where the time went
+    #[arg(long)]
+    pub summary: bool,
+
+    /// After the chart, list the replicas that the coordinator sent requests to, and how long each took to respond
+    #[arg(long)]
+    pub replicas: bool,
+
+    /// After the chart, sum up what each replica read: live rows, tombstones, sstables, cache and read repairs
+    #[arg(long)]
+    pub reads: bool,
+
+    /// With `--reads`, warn when more than this share of the rows a replica read were tombstones
+    #[arg(long, default_value_t)]
+    pub max_tombstone_ratio: MaxTombstoneRatio,
+
+    /// With `--reads`, warn when a replica read from more than this many sstables
+    #[arg(long, default_value_t)]
+    pub max_sstables: MaxSstables,
+
+    /// After the chart, break a lightweight transaction down into its paxos phases, with a timeline of each and any
+    /// signs of contention
+    #[arg(long)]
+    pub paxos: bool,
+
+    /// After the chart, flag anything unusual about the trace, such as long gaps, throttling, timeouts, speculative
+    /// retries, dead replicas and writes forwarded to another datacenter
+    #[arg(long)]
+    pub lint: bool,
+
+    /// With `--lint`, flag gaps of at least this many microseconds between two events of the same span
+    #[arg(long, default_value_t)]
+    pub max_gap: MaxGap,
+
+    /// Whether to write out the chart and reports as text, or just the reports as JSON
+    #[arg(value_enum, long, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Highlight the critical path, the chain of events that determined how long the session took, by shading the
+    /// waterfall of every other event
+    #[arg(long)]
+    pub critical_path: bool,
+
+    /// Replace literal values in the query, IP addresses and usernames, so that the trace can be shared. Also applies
+    /// to `seella db export`
+    #[arg(long)]
+    pub redact: bool,
 }
 
 //   ___  ___ ___ ___    _ _____ ___ ___  _  _   __  __  ___  ___  ___
@@ -61,6 +191,22 @@ pub enum OperationMode {
     Csv(CsvModeOptions),
     /// Use a live database as a data source.
     Db(DbModeOptions),
+    /// Use the tracing output printed by cqlsh as a data source
+    Cqlsh(CqlshModeOptions),
+    /// Use a seella archive, as written by `seella db export`, as a data source
+    Archive(ArchiveModeOptions),
+    /// Browse a session interactively in the terminal
+    Tui(TuiModeOptions),
+    /// Compare two traces of the same query, such as a slow run against a fast one
+    Diff(DiffModeOptions),
+    /// Aggregate the durations of many sessions, grouped by query
+    Stats(StatsModeOptions),
+    /// Merge many sessions of the same query into a single tree, showing what a typical run looks like
+    Profile(ProfileModeOptions),
+    /// List the slowest sessions, and show each of them in full
+    Top(TopModeOptions),
+    /// List the entries of the slow query log, or show the session traced for one of them
+    SlowLog(SlowLogModeOptions),
 }
 
 impl Default for OperationMode {
@@ -198,3 +344,154 @@ impl Deref for MaxActivityWidth {
         &self.0
     }
 }
+
+//   ___  ___  _     ___  ___
+//  / __|/ _ \| |   / _ \| _ \
+// | (__| (_) | |__| (_) |   /
+//  \___|\___/|____|\___/|_|_\
+
+/// When to colour the output.
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub enum ColorChoice {
+    /// Colour the output when writing to a terminal, unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// What the colours of the waterfall bars represent.
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub enum ColorBy {
+    #[default]
+    Node,
+    /// The shard, or the thread for traces from Cassandra
+    Shard,
+}
+
+//  ___ ___  ___ __  __   _ _____
+// | __/ _ \| _ \  \/  | /_\_   _|
+// | _| (_) |   / |\/| |/ _ \| |
+// |_| \___/|_|_\_|  |_/_/ \_\_|
+
+/// How to write out the session.
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// The waterfall chart, followed by any reports asked for
+    #[default]
+    Text,
+    /// Only the reports asked for, such as `--summary` or `--lint`, as a single JSON object
+    Json,
+}
+
+//  ___ ___   _   ___  ___
+// | _ \ __| /_\ |   \/ __|
+// |   / _| / _ \| |) \__ \
+// |_|_\___/_/ \_\___/|___/
+
+/// Default share of the rows read by a replica that can be tombstones before `--reads` warns about it.
+///
+/// Type to provide a correct `Default::default()` f64 for clap.
+#[derive(Debug, Clone)]
+pub struct MaxTombstoneRatio(pub f64);
+
+impl Default for MaxTombstoneRatio {
+    fn default() -> Self {
+        Self(0.5)
+    }
+}
+
+impl Display for MaxTombstoneRatio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for MaxTombstoneRatio {
+    type Err = ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(f64::from_str(s)?))
+    }
+}
+
+impl Deref for MaxTombstoneRatio {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Default number of sstables a replica can read from before `--reads` warns about it.
+///
+/// Type to provide a correct `Default::default()` usize for clap.
+#[derive(Debug, Clone)]
+pub struct MaxSstables(pub usize);
+
+impl Default for MaxSstables {
+    fn default() -> Self {
+        Self(10)
+    }
+}
+
+impl Display for MaxSstables {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for MaxSstables {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(usize::from_str(s)?))
+    }
+}
+
+impl Deref for MaxSstables {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+//  _    ___ _  _ _____
+// | |  |_ _| \| |_   _|
+// | |__ | || .` | | |
+// |____|___|_|\_| |_|
+
+/// Default number of microseconds between two events of the same span before `--lint` flags it.
+///
+/// Type to provide a correct `Default::default()` i64 for clap.
+#[derive(Debug, Clone)]
+pub struct MaxGap(pub i64);
+
+impl Default for MaxGap {
+    fn default() -> Self {
+        Self(1000)
+    }
+}
+
+impl Display for MaxGap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for MaxGap {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(i64::from_str(s)?))
+    }
+}
+
+impl Deref for MaxGap {
+    type Target = i64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
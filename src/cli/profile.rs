@@ -0,0 +1,15 @@
+use super::SessionsSource;
+use clap::Args;
+use regex::Regex;
+
+/// Options that are specific to the profile mode of operation.
+#[derive(Debug, Args, Clone)]
+pub struct ProfileModeOptions {
+    /// Only profile queries matching this regular expression, once their literal values have been replaced with `?`
+    #[arg(long)]
+    pub query: Option<Regex>,
+
+    /// Where to read the sessions from
+    #[command(subcommand)]
+    pub source: SessionsSource,
+}
@@ -0,0 +1,45 @@
+use super::{ArchiveModeOptions, CqlshModeOptions, CsvModeOptions, DbModeOptions, OperationMode};
+use clap::Subcommand;
+
+/// The data sources that a single session can be loaded from, for the modes of operation that wrap another; the same
+/// as the other modes of operation.
+#[derive(Debug, Subcommand, Clone)]
+pub enum SessionSource {
+    /// Use a pair of CSVs as a data source
+    Csv(CsvModeOptions),
+    /// Use a live database as a data source
+    Db(DbModeOptions),
+    /// Use the tracing output printed by cqlsh as a data source
+    Cqlsh(CqlshModeOptions),
+    /// Use a seella archive, as written by `seella db export`, as a data source
+    Archive(ArchiveModeOptions),
+}
+
+impl SessionSource {
+    /// The same data source, but for a different session.
+    ///
+    /// Returns `None` for cqlsh, as its output only ever holds the one session.
+    pub fn with_session(&self, session_id: &str) -> Option<Self> {
+        let mut source = self.clone();
+        match source {
+            SessionSource::Csv(ref mut options) => options.session_id = session_id.to_string(),
+            SessionSource::Db(ref mut options) => options.session_id = Some(session_id.to_string()),
+            SessionSource::Cqlsh(_) => return None,
+            SessionSource::Archive(ref mut options) => {
+                options.session_id = Some(session_id.to_string())
+            }
+        }
+        Some(source)
+    }
+}
+
+impl From<SessionSource> for OperationMode {
+    fn from(value: SessionSource) -> Self {
+        match value {
+            SessionSource::Csv(options) => OperationMode::Csv(options),
+            SessionSource::Db(options) => OperationMode::Db(options),
+            SessionSource::Cqlsh(options) => OperationMode::Cqlsh(options),
+            SessionSource::Archive(options) => OperationMode::Archive(options),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::{ArchivePath, CsvFormatOptions, DbAddr, DbDriverOptions, EventsPath, SessionsPath};
+use chrono::{DateTime, Duration, Utc};
+use clap::{Args, Subcommand};
+use regex::Regex;
+use std::{net::IpAddr, ops::Deref, path::PathBuf, str::FromStr};
+use thiserror::Error;
+
+/// The data sources that many sessions can be read from at once, for the modes of operation that look across
+/// sessions rather than at a single one.
+#[derive(Debug, Subcommand, Clone)]
+pub enum SessionsSource {
+    /// Use every session in a pair of CSVs
+    Csv(CsvSessionsOptions),
+    /// Use the sessions started within a window of time, from a live database
+    Db(DbSessionsOptions),
+    /// Use every session in a seella archive, as written by `seella db export`
+    Archive(ArchiveSessionsOptions),
+}
+
+/// Options for reading every session from a pair of CSVs.
+#[derive(Debug, Args, Clone, Default)]
+pub struct CsvSessionsOptions {
+    /// Path to the CSV containing the sessions data, or `-` for stdin. May be gzip or zstd compressed
+    #[arg(short, long, default_value_t)]
+    pub sessions_path: SessionsPath,
+
+    /// Path to the CSV containing the events data, or `-` for stdin. May be gzip or zstd compressed
+    #[arg(short, long, default_value_t)]
+    pub events_path: EventsPath,
+
+    /// Path to a directory or tarball containing both a sessions.csv and events.csv, or `-` for a tarball on stdin.
+    /// Used instead of the sessions and events paths
+    #[arg(short, long, conflicts_with_all = ["sessions_path", "events_path"])]
+    pub bundle: Option<PathBuf>,
+
+    /// Skip rows that can't be read, printing a warning for each, rather than failing
+    #[arg(long)]
+    pub lenient: bool,
+
+    #[command(flatten)]
+    pub format: CsvFormatOptions,
+}
+
+/// Options for reading the sessions started within a window of time from a live database.
+#[derive(Debug, Args, Clone, Default)]
+pub struct DbSessionsOptions {
+    /// Socket Address (IP address and port) for the database connection. See [std::net::SocketAddr::from_str].
+    #[arg(short, long, default_value_t)]
+    pub addr: DbAddr,
+
+    #[command(flatten)]
+    pub driver: DbDriverOptions,
+
+    /// Only sessions started at or after this time, either as an RFC 3339 timestamp such as `2023-08-13T01:48:00Z`,
+    /// or as a time ago such as `30m`, `2h` or `1d`. Leaving this out means reading everything the cluster has
+    /// kept, which is much heavier
+    #[arg(long)]
+    pub since: Option<Timestamp>,
+
+    /// Only sessions started before this time; see `--since`
+    #[arg(long)]
+    pub until: Option<Timestamp>,
+}
+
+/// Options for reading every session from a seella archive.
+#[derive(Debug, Args, Clone, Default)]
+pub struct ArchiveSessionsOptions {
+    /// Path to the archive written by `seella db export`, or `-` for stdin. May be gzip or zstd compressed
+    #[arg(short, long, default_value_t)]
+    pub path: ArchivePath,
+}
+
+/// Options for picking out sessions by who ran them and what they ran against.
+#[derive(Debug, Args, Clone, Default)]
+pub struct SessionFilterOptions {
+    /// Only sessions coordinated by this node. May be given more than once
+    #[arg(long)]
+    pub coordinator: Vec<IpAddr>,
+
+    /// Only sessions from this client. May be given more than once
+    #[arg(long)]
+    pub client: Vec<IpAddr>,
+
+    /// Only sessions of this command, i.e. `QUERY`
+    #[arg(long)]
+    pub command: Option<String>,
+
+    /// Only sessions run by this user
+    #[arg(long)]
+    pub username: Option<String>,
+
+    /// Only sessions whose query is run against this keyspace, as named in the query
+    #[arg(long)]
+    pub keyspace: Option<String>,
+
+    /// Only sessions whose query is run against this table, as named in the query
+    #[arg(long)]
+    pub table: Option<String>,
+
+    /// Only sessions that took at least this many microseconds
+    #[arg(long)]
+    pub min_session_duration: Option<i64>,
+
+    /// Only sessions with a query matching this regular expression, once its literal values have been replaced with
+    /// `?`
+    #[arg(long)]
+    pub query: Option<Regex>,
+}
+
+/// A point in time given on the command line, either as an RFC 3339 timestamp or as a time ago.
+///
+/// Type to parse both forms into a DateTime for clap.
+#[derive(Debug, Clone, Copy)]
+pub struct Timestamp(pub DateTime<Utc>);
+
+/// The time given for an option like `--since` could not be understood.
+#[derive(Debug, Error)]
+#[error("expected an RFC 3339 timestamp such as 2023-08-13T01:48:00Z, or a time ago such as 30m, 2h or 1d")]
+pub struct InvalidTimestamp;
+
+impl FromStr for Timestamp {
+    type Err = InvalidTimestamp;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Self(datetime.with_timezone(&Utc)));
+        }
+
+        let unit = s.chars().last().ok_or(InvalidTimestamp)?;
+        // Unsigned, as a time ago can't be in the future
+        let amount: u64 = s[..s.len() - unit.len_utf8()]
+            .parse()
+            .map_err(|_| InvalidTimestamp)?;
+        let seconds = match unit {
+            's' => Some(amount),
+            'm' => amount.checked_mul(60),
+            'h' => amount.checked_mul(60 * 60),
+            'd' => amount.checked_mul(24 * 60 * 60),
+            _ => None,
+        };
+        let ago = seconds
+            .and_then(|seconds| Duration::from_std(std::time::Duration::from_secs(seconds)).ok())
+            .ok_or(InvalidTimestamp)?;
+        Utc::now()
+            .checked_sub_signed(ago)
+            .map(Self)
+            .ok_or(InvalidTimestamp)
+    }
+}
+
+impl Deref for Timestamp {
+    type Target = DateTime<Utc>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,101 @@
+use super::{
+    CsvFormatOptions, CsvModeOptions, DbModeOptions, DbSessionsOptions, EventsPath, OperationMode,
+    SessionsPath,
+};
+use clap::{Args, Subcommand};
+use std::{ffi::OsString, fmt::Display, ops::Deref, path::PathBuf};
+
+/// Options that are specific to the slow log mode of operation.
+#[derive(Debug, Args, Clone)]
+pub struct SlowLogModeOptions {
+    /// Show the session traced for an entry rather than listing them, given as either its number in the list, its
+    /// session id, or its start time
+    #[arg(long)]
+    pub show: Option<String>,
+
+    /// Where to read the slow log from
+    #[command(subcommand)]
+    pub source: SlowLogSource,
+}
+
+/// The data sources that the slow query log can be read from.
+#[derive(Debug, Subcommand, Clone)]
+pub enum SlowLogSource {
+    /// Use an export of the slow log, along with the sessions and events CSVs for showing an entry
+    Csv(CsvSlowLogOptions),
+    /// Use the entries for queries started within a window of time, from a live database
+    Db(DbSessionsOptions),
+}
+
+/// Options for reading the slow log from an exported CSV.
+#[derive(Debug, Args, Clone, Default)]
+pub struct CsvSlowLogOptions {
+    /// Path to the CSV containing the `system_traces.node_slow_log` data, or `-` for stdin. May be gzip or zstd
+    /// compressed
+    #[arg(short = 'l', long, default_value_t)]
+    pub slow_log_path: SlowLogPath,
+
+    /// Path to the CSV containing the sessions data. May be gzip or zstd compressed
+    #[arg(short, long, default_value_t)]
+    pub sessions_path: SessionsPath,
+
+    /// Path to the CSV containing the events data. May be gzip or zstd compressed
+    #[arg(short, long, default_value_t)]
+    pub events_path: EventsPath,
+
+    #[command(flatten)]
+    pub format: CsvFormatOptions,
+}
+
+impl SlowLogSource {
+    /// The same data source, for showing the session traced for one of the entries.
+    pub fn with_session(&self, session_id: &str) -> OperationMode {
+        match self {
+            SlowLogSource::Csv(options) => OperationMode::Csv(CsvModeOptions {
+                session_id: session_id.to_string(),
+                sessions_path: options.sessions_path.clone(),
+                events_path: options.events_path.clone(),
+                format: options.format.clone(),
+                ..Default::default()
+            }),
+            SlowLogSource::Db(options) => OperationMode::Db(DbModeOptions {
+                session_id: Some(session_id.to_string()),
+                addr: options.addr.clone(),
+                driver: options.driver.clone(),
+                action: None,
+            }),
+        }
+    }
+}
+
+/// Default path to the [slow log][crate::SlowLogRecord] source.
+///
+/// Type to provide a correct `Default::default()` PathBuf for clap.
+#[derive(Debug, Clone)]
+pub struct SlowLogPath(pub PathBuf);
+
+impl Default for SlowLogPath {
+    fn default() -> Self {
+        Self(PathBuf::from("node_slow_log.csv"))
+    }
+}
+
+impl Display for SlowLogPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+impl From<OsString> for SlowLogPath {
+    fn from(value: OsString) -> Self {
+        Self(PathBuf::from(value))
+    }
+}
+
+impl Deref for SlowLogPath {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,10 @@
+use super::SessionsSource;
+use clap::Args;
+
+/// Options that are specific to the stats mode of operation.
+#[derive(Debug, Args, Clone)]
+pub struct StatsModeOptions {
+    /// Where to read the sessions from
+    #[command(subcommand)]
+    pub source: SessionsSource,
+}
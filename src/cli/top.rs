@@ -0,0 +1,56 @@
+use super::{SessionFilterOptions, SessionsSource};
+use clap::Args;
+use std::{fmt::Display, num::ParseIntError, ops::Deref, str::FromStr};
+
+/// Options that are specific to the top mode of operation.
+#[derive(Debug, Args, Clone)]
+pub struct TopModeOptions {
+    /// How many of the slowest sessions to show
+    #[arg(short = 'n', long, default_value_t)]
+    pub count: TopCount,
+
+    /// Only list the slowest sessions, rather than showing each in full after the list
+    #[arg(long)]
+    pub list: bool,
+
+    #[command(flatten)]
+    pub filter: SessionFilterOptions,
+
+    /// Where to read the sessions from
+    #[command(subcommand)]
+    pub source: SessionsSource,
+}
+
+/// Default number of sessions to show.
+///
+/// Type to provide a correct `Default::default()` usize for clap.
+#[derive(Debug, Clone)]
+pub struct TopCount(pub usize);
+
+impl Default for TopCount {
+    fn default() -> Self {
+        Self(10)
+    }
+}
+
+impl Display for TopCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for TopCount {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(usize::from_str(s)?))
+    }
+}
+
+impl Deref for TopCount {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
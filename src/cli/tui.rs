@@ -0,0 +1,10 @@
+use super::SessionSource;
+use clap::Args;
+
+/// Options that are specific to the tui mode of operation.
+#[derive(Debug, Args, Clone)]
+pub struct TuiModeOptions {
+    /// Where to load the session from
+    #[command(subcommand)]
+    pub source: SessionSource,
+}
@@ -0,0 +1,63 @@
+//! Hand-rolled ANSI styling for the text output.
+
+use crate::cli::ColorChoice;
+use std::{collections::HashMap, io::IsTerminal};
+
+pub(crate) const BOLD: &str = "1";
+pub(crate) const DIM: &str = "2";
+pub(crate) const SLOW: &str = "1;31";
+
+/// The colours used to tell nodes or shards apart, chosen to be readable on both light and dark terminals.
+pub(crate) const PALETTE: &[&str] = &["36", "35", "33", "32", "34", "31", "96", "95", "93", "92"];
+
+/// Hands out colours from the [PALETTE] in the order that nodes or shards are first seen, so that each keeps its colour
+/// throughout the chart.
+#[derive(Debug, Default)]
+pub(crate) struct Palette {
+    assigned: HashMap<String, &'static str>,
+}
+
+impl Palette {
+    pub(crate) fn get(&mut self, key: &str) -> &'static str {
+        let next = PALETTE[self.assigned.len() % PALETTE.len()];
+        self.assigned.entry(key.to_string()).or_insert(next)
+    }
+}
+
+/// Wraps the text in the given ANSI style, if colour is enabled.
+///
+/// Any padding should be applied before painting, since the escape codes count towards the width of a formatted
+/// string.
+pub(crate) fn paint(text: &str, style: &str, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b[{style}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+impl ColorChoice {
+    /// Decides whether to colour output written to stdout, following <https://no-color.org>.
+    ///
+    /// `auto` becomes `always` when stdout is a terminal and `NO_COLOR` is unset or empty, and `never` otherwise.
+    pub fn resolve(self) -> Self {
+        match self {
+            ColorChoice::Auto => {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                if !no_color && std::io::stdout().is_terminal() {
+                    ColorChoice::Always
+                } else {
+                    ColorChoice::Never
+                }
+            }
+            choice => choice,
+        }
+    }
+
+    /// Whether to write colour.
+    ///
+    /// `auto` is treated as `never` here, since we can't know where the output ends up; see [ColorChoice::resolve].
+    pub fn enabled(&self) -> bool {
+        matches!(self, ColorChoice::Always)
+    }
+}
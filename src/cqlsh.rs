@@ -0,0 +1,236 @@
+use crate::{
+    input,
+    records::{EventRecord, SessionRecord},
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::{io::BufRead, net::IpAddr, path::PathBuf};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The activity cqlsh prints as the final row of every trace.
+const REQUEST_COMPLETE: &str = "Request complete";
+
+/// A source for the data based on the text table printed by cqlsh after a query when `TRACING ON` is set.
+///
+/// This is what you get when someone copies the trace out of their terminal:
+///
+/// ```text
+/// Tracing session: 74ff67c0-397b-11ee-8ca4-9688db6cc0f1
+///
+///  activity                      | timestamp                  | source     | source_elapsed | client
+/// -------------------------------+----------------------------+------------+----------------+------------
+///             Execute CQL3 query | 2023-08-13 01:48:10.172000 | 172.17.0.2 |              0 | 172.17.0.2
+///  Parsing a statement [shard 0] | 2023-08-13 01:48:10.172041 | 172.17.0.2 |             -- | 172.17.0.2
+///               Request complete | 2023-08-13 01:48:10.172346 | 172.17.0.2 |            346 | 172.17.0.2
+/// ```
+///
+/// The table carries a lot less information than `system_traces`; there are no span ids, so every event is a root
+/// event, and there are no event ids, so these are generated from the row number. The query parameters are not
+/// printed by cqlsh and are left empty.
+///
+/// A path of `-` reads the text from stdin, and compressed files are decompressed transparently.
+#[derive(Debug)]
+pub struct CqlshSource<'a> {
+    path: &'a PathBuf,
+}
+
+impl<'a> CqlshSource<'a> {
+    pub fn new(path: &'a PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+/// The kinds of errors that can be experienced while parsing the text output of cqlsh.
+#[derive(Debug, Error)]
+pub enum CqlshParsingError {
+    #[error("could not find the header row of the tracing table")]
+    HeaderNotFound,
+    #[error("the tracing table does not contain any rows")]
+    NoRows,
+    #[error("line {line}: expected 5 columns separated by '|', found {found}")]
+    ColumnCount { line: usize, found: usize },
+    #[error("line {line}: could not parse the timestamp {value:?}")]
+    Timestamp { line: usize, value: String },
+    #[error("line {line}: could not parse the ip address {value:?}")]
+    IpAddr { line: usize, value: String },
+    #[error("line {line}: could not parse the source_elapsed {value:?}")]
+    SourceElapsed { line: usize, value: String },
+    #[error("line {line}: could not parse the tracing session id {value:?}")]
+    SessionId { line: usize, value: String },
+    #[error("there were issues reading the input")]
+    IoError(#[from] std::io::Error),
+}
+
+/// A single row of the tracing table.
+struct CqlshRow {
+    activity: String,
+    timestamp: DateTime<Utc>,
+    source: IpAddr,
+    source_elapsed: i32,
+    client: IpAddr,
+}
+
+impl<'a> CqlshSource<'a> {
+    pub fn get_data(&self) -> Result<(SessionRecord, Vec<EventRecord>), CqlshParsingError> {
+        parse_cqlsh_trace(input::open(self.path)?)
+    }
+}
+
+/// Parses the text table printed by cqlsh into the records used to build a [Session][crate::Session].
+///
+/// The first row of the table describes the request itself, and the `Request complete` row gives the total duration;
+/// all of the rows in between are the events of the session.
+///
+/// The `Tracing session:` line is often left behind when only the table is copied out of the terminal, so it isn't
+/// required; without it, the session id is the nil UUID.
+pub fn parse_cqlsh_trace(
+    reader: impl BufRead,
+) -> Result<(SessionRecord, Vec<EventRecord>), CqlshParsingError> {
+    let mut session_id = Uuid::nil();
+    let mut in_table = false;
+    let mut rows = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        let line_number = i + 1;
+
+        if let Some(id) = trimmed.strip_prefix("Tracing session:") {
+            session_id = Uuid::try_parse(id.trim()).map_err(|_| CqlshParsingError::SessionId {
+                line: line_number,
+                value: id.trim().to_string(),
+            })?;
+            continue;
+        }
+
+        if !in_table {
+            // The header row is followed by a separator row, which we skip along with the header.
+            in_table = trimmed.starts_with("activity") && trimmed.contains('|');
+            continue;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with("---") {
+            continue;
+        }
+
+        let row = parse_row(trimmed, line_number)?;
+        let complete = row.activity == REQUEST_COMPLETE;
+        rows.push(row);
+
+        if complete {
+            break;
+        }
+    }
+
+    if !in_table {
+        return Err(CqlshParsingError::HeaderNotFound);
+    }
+
+    let mut rows = rows.into_iter();
+    let request = rows.next().ok_or(CqlshParsingError::NoRows)?;
+    let mut rows: Vec<CqlshRow> = rows.collect();
+
+    let duration = match rows.last() {
+        Some(row) if row.activity == REQUEST_COMPLETE => rows.pop().unwrap().source_elapsed,
+        _ => rows.iter().map(|row| row.source_elapsed).max().unwrap_or(0),
+    };
+
+    let session_record = SessionRecord {
+        session_id,
+        client: request.client,
+        command: String::from("QUERY"),
+        coordinator: request.source,
+        duration,
+        parameters: String::new(),
+        request: request.activity,
+        started_at: request.timestamp,
+        request_size: None,
+        response_size: None,
+        username: None,
+    };
+
+    let event_records = rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let (activity, thread) = split_thread(&row.activity);
+            EventRecord {
+                session_id,
+                event_id: Uuid::from_u64_pair(0, i as u64 + 1),
+                activity,
+                source: row.source,
+                source_elapsed: row.source_elapsed,
+                thread,
+                scylla_parent_id: None,
+                scylla_span_id: None,
+            }
+        })
+        .collect();
+
+    Ok((session_record, event_records))
+}
+
+/// Splits a row on the column separators, working from the right so that a `|` within the activity is preserved.
+fn parse_row(line: &str, line_number: usize) -> Result<CqlshRow, CqlshParsingError> {
+    let mut columns: Vec<&str> = line.rsplitn(5, '|').map(str::trim).collect();
+    if columns.len() != 5 {
+        return Err(CqlshParsingError::ColumnCount {
+            line: line_number,
+            found: columns.len(),
+        });
+    }
+    columns.reverse();
+
+    let timestamp = parse_timestamp(columns[1]).ok_or_else(|| CqlshParsingError::Timestamp {
+        line: line_number,
+        value: columns[1].to_string(),
+    })?;
+    let ip_addr = |value: &str| {
+        value.parse().map_err(|_| CqlshParsingError::IpAddr {
+            line: line_number,
+            value: value.to_string(),
+        })
+    };
+    let source_elapsed = match columns[3] {
+        // Cassandra prints a null source_elapsed for the first event on the coordinator.
+        "--" | "" => 0,
+        value => value
+            .parse()
+            .map_err(|_| CqlshParsingError::SourceElapsed {
+                line: line_number,
+                value: value.to_string(),
+            })?,
+    };
+
+    Ok(CqlshRow {
+        activity: columns[0].to_string(),
+        timestamp,
+        source: ip_addr(columns[2])?,
+        source_elapsed,
+        client: ip_addr(columns[4])?,
+    })
+}
+
+/// cqlsh prints timestamps without a timezone by default, but may be configured to include one.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%z")
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+                .map(|datetime| datetime.and_utc())
+        })
+        .ok()
+}
+
+/// Scylla appends the thread to the activity, i.e. `Parsing a statement [shard 0]`.
+///
+/// Cassandra does not, in which case the thread is left empty.
+fn split_thread(activity: &str) -> (String, String) {
+    if let Some(rest) = activity.strip_suffix(']') {
+        if let Some((activity, thread)) = rest.rsplit_once(" [") {
+            return (activity.to_string(), thread.to_string());
+        }
+    }
+
+    (activity.to_string(), String::new())
+}
@@ -1,24 +1,285 @@
-use crate::records::{EventRecord, SessionRecord};
-use std::{fs::File, io::BufReader, path::PathBuf};
+use crate::{
+    index::EventsIndex,
+    input,
+    records::{EventRecord, SessionRecord, SlowLogRecord},
+    CsvFormatOptions,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::Read,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 use uuid::Uuid;
 
 /// A source for the data based on an exported CSV.
 #[derive(Debug)]
 pub struct CsvSource<'a> {
-    sessions: &'a PathBuf,
-    events: &'a PathBuf,
-    session_id: Uuid,
+    input: CsvInput<'a>,
+    /// The session to read, or `None` for every session
+    session_id: Option<Uuid>,
+    format: CsvFormat,
+    use_index: bool,
+    full_scan: bool,
+}
+
+/// Where the exported CSVs are to be read from.
+///
+/// Any of the files may be gzip or zstd compressed, and are decompressed transparently.
+#[derive(Debug)]
+pub enum CsvInput<'a> {
+    /// A separate `sessions.csv` and `events.csv`; either one of which may be `-` for stdin.
+    Pair {
+        sessions: &'a PathBuf,
+        events: &'a PathBuf,
+    },
+    /// A directory or tarball containing both a `sessions.csv` and `events.csv`, or `-` for a tarball on stdin.
+    Bundle(&'a PathBuf),
+}
+
+/// The options that were given to `COPY TO` when exporting the CSVs, which affect how they are to be read.
+#[derive(Debug, Clone)]
+pub struct CsvFormat {
+    /// i.e. `WITH DELIMITER = '|'`
+    pub delimiter: u8,
+    /// i.e. `WITH QUOTE = '''`
+    pub quote: u8,
+    /// i.e. `WITH ESCAPE = '\'`. If not set, quotes are escaped by doubling them
+    pub escape: Option<u8>,
+    /// Whether the first row contains the column names, i.e. `WITH HEADER = TRUE`
+    pub has_headers: bool,
+    /// The string written for null values, i.e. `WITH NULL = 'null'`
+    pub null: Option<String>,
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            has_headers: true,
+            null: None,
+        }
+    }
+}
+
+impl From<&CsvFormatOptions> for CsvFormat {
+    fn from(options: &CsvFormatOptions) -> Self {
+        Self {
+            delimiter: *options.delimiter,
+            quote: *options.quote,
+            escape: options.escape.as_deref().copied(),
+            has_headers: !options.no_header,
+            null: options.null.clone(),
+        }
+    }
+}
+
+impl CsvFormat {
+    pub(crate) fn reader<R: Read>(&self, reader: R) -> csv::Reader<R> {
+        csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .escape(self.escape)
+            .double_quote(self.escape.is_none())
+            .has_headers(self.has_headers)
+            .from_reader(reader)
+    }
+
+    /// The column names of the CSV, normalised so that `Session ID`, `"session_id"` and `SESSION_ID` are all read as
+    /// `session_id`.
+    ///
+    /// Returns `None` if the CSV has no header row, in which case the column order `COPY TO` uses by default is
+    /// assumed; see [ExportedRecord::DEFAULT_HEADERS].
+    pub(crate) fn headers<R: Read>(
+        &self,
+        reader: &mut csv::Reader<R>,
+    ) -> Result<Option<csv::ByteRecord>, csv::Error> {
+        if !self.has_headers {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            reader
+                .byte_headers()?
+                .iter()
+                .map(normalise_header)
+                .collect(),
+        ))
+    }
+
+    /// The index of the `session_id` column, which is always the first column when there's no header row.
+    pub(crate) fn session_id_column(&self, headers: Option<&csv::ByteRecord>) -> Option<usize> {
+        match headers {
+            Some(headers) => headers.iter().position(|header| header == b"session_id"),
+            None => Some(0),
+        }
+    }
+}
+
+fn normalise_header(header: &[u8]) -> Vec<u8> {
+    let header = header
+        .strip_prefix("\u{feff}".as_bytes())
+        .unwrap_or(header)
+        .trim_ascii();
+    let header = header
+        .strip_prefix(b"\"")
+        .and_then(|header| header.strip_suffix(b"\""))
+        .unwrap_or(header);
+
+    header
+        .iter()
+        .map(|byte| match byte {
+            b' ' | b'-' => b'_',
+            byte => byte.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// A record type which can be read from an exported CSV.
+pub(crate) trait ExportedRecord: DeserializeOwned {
+    /// The column orders used by `COPY TO` by default for Scylla and Cassandra, for CSVs without a header row.
+    const DEFAULT_HEADERS: &'static [&'static [&'static str]];
+
+    /// Whether the rows of a session are contiguous in an export, as they are when `session_id` is the partition key.
+    const CONTIGUOUS: bool = true;
+
+    /// Picks the default column order with the same number of columns as the row.
+    fn default_headers(len: usize) -> Option<csv::ByteRecord> {
+        Self::DEFAULT_HEADERS
+            .iter()
+            .find(|headers| headers.len() == len)
+            .map(|headers| csv::ByteRecord::from(headers.to_vec()))
+    }
+}
+
+impl ExportedRecord for SessionRecord {
+    const DEFAULT_HEADERS: &'static [&'static [&'static str]] = &[
+        &[
+            "session_id",
+            "client",
+            "command",
+            "coordinator",
+            "duration",
+            "parameters",
+            "request",
+            "request_size",
+            "response_size",
+            "started_at",
+            "username",
+        ],
+        &[
+            "session_id",
+            "client",
+            "command",
+            "coordinator",
+            "duration",
+            "parameters",
+            "request",
+            "started_at",
+        ],
+    ];
+}
+
+impl ExportedRecord for EventRecord {
+    const DEFAULT_HEADERS: &'static [&'static [&'static str]] = &[
+        &[
+            "session_id",
+            "event_id",
+            "activity",
+            "scylla_parent_id",
+            "scylla_span_id",
+            "source",
+            "source_elapsed",
+            "thread",
+        ],
+        &[
+            "session_id",
+            "event_id",
+            "activity",
+            "source",
+            "source_elapsed",
+            "thread",
+        ],
+    ];
+}
+
+impl ExportedRecord for SlowLogRecord {
+    const DEFAULT_HEADERS: &'static [&'static [&'static str]] = &[&[
+        "start_time",
+        "node_ip",
+        "shard",
+        "command",
+        "date",
+        "duration",
+        "parameters",
+        "session_id",
+        "source_ip",
+        "table_names",
+        "username",
+    ]];
+
+    // Partitioned by `start_time`, so a session's entries from different nodes may be anywhere in the export
+    const CONTIGUOUS: bool = false;
 }
 
 impl<'a> CsvSource<'a> {
     pub fn new(sessions: &'a PathBuf, events: &'a PathBuf, session_id: Uuid) -> Self {
         Self {
-            sessions,
-            events,
-            session_id,
+            input: CsvInput::Pair { sessions, events },
+            session_id: Some(session_id),
+            format: CsvFormat::default(),
+            use_index: false,
+            full_scan: false,
+        }
+    }
+
+    pub fn from_bundle(bundle: &'a PathBuf, session_id: Uuid) -> Self {
+        Self {
+            input: CsvInput::Bundle(bundle),
+            session_id: Some(session_id),
+            format: CsvFormat::default(),
+            use_index: false,
+            full_scan: false,
         }
     }
+
+    /// Reads every session rather than a single one; see [CsvSource::get_all_data].
+    pub fn every_session(input: CsvInput<'a>) -> Self {
+        Self {
+            input,
+            session_id: None,
+            format: CsvFormat::default(),
+            use_index: false,
+            full_scan: false,
+        }
+    }
+
+    /// Read the CSVs according to the options they were exported with.
+    pub fn with_format(mut self, format: CsvFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Use an [EventsIndex] to find the session's events, building it if needed.
+    ///
+    /// Only applies to uncompressed events CSVs outside of a tarball.
+    pub fn with_index(mut self, use_index: bool) -> Self {
+        self.use_index = use_index;
+        self
+    }
+
+    /// Read the entire events CSV, rather than stopping after the session's rows.
+    ///
+    /// Only needed for files where the rows of a session are not contiguous, such as when several exports have been
+    /// concatenated.
+    pub fn with_full_scan(mut self, full_scan: bool) -> Self {
+        self.full_scan = full_scan;
+        self
+    }
 }
 
 /// The kinds of errors that can be experienced while parsing the data from the CSV.
@@ -26,51 +287,388 @@ impl<'a> CsvSource<'a> {
 pub enum CsvParsingError {
     #[error("the provided session id {0} could not be found")]
     SessionNotFound(Uuid),
-    #[error("there were issues deserializing the session data")]
-    SessionDeserializationErrors(Vec<csv::Error>),
-    #[error("there were issues deserializing the event data")]
-    EventDeserializationErrors(Vec<csv::Error>),
+    #[error("there were issues deserializing the session data:{}", list_row_errors(.0))]
+    SessionDeserializationErrors(Vec<RowError>),
+    #[error("there were issues deserializing the event data:{}", list_row_errors(.0))]
+    EventDeserializationErrors(Vec<RowError>),
+    #[error("there were issues deserializing the slow log data:{}", list_row_errors(.0))]
+    SlowLogDeserializationErrors(Vec<RowError>),
+    #[error("the sessions and events can't both be read from stdin")]
+    BothFromStdin,
+    #[error("could not find {0} in the bundle")]
+    MissingFromBundle(&'static str),
+    #[error("could not read the CSV headers: {0}")]
+    Headers(csv::Error),
+    #[error("the CSV does not have a session_id column")]
+    MissingColumn,
+    #[error("there was an issue with the events index: {0}")]
+    Index(csv::Error),
     #[error("there were issues finding the files")]
     IoError(#[from] std::io::Error),
 }
 
+fn list_row_errors(errors: &[RowError]) -> String {
+    errors.iter().map(|error| format!("\n  {error}")).collect()
+}
+
+/// A row of an exported CSV that could not be read, along with where to find it and what was wrong with it.
+#[derive(Debug)]
+pub struct RowError {
+    /// The line the row starts on
+    pub line: Option<u64>,
+    /// The column that could not be deserialized, if the row could be split into columns at all
+    pub column: Option<String>,
+    /// The offending value within that column
+    pub value: Option<String>,
+    pub error: csv::Error,
+}
+
+impl RowError {
+    fn new(error: csv::Error, headers: Option<&csv::ByteRecord>, record: &csv::ByteRecord) -> Self {
+        let field = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => err.field().map(|field| field as usize),
+            _ => None,
+        };
+        let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+
+        Self {
+            line: error.position().map(csv::Position::line),
+            column: field.and_then(|field| headers?.get(field)).map(text),
+            value: field.and_then(|field| record.get(field)).map(text),
+            error,
+        }
+    }
+}
+
+impl Display for RowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}")?,
+            None => write!(f, "unknown line")?,
+        }
+        if let Some(column) = &self.column {
+            write!(f, ", column {column}")?;
+        }
+        if let Some(value) = &self.value {
+            write!(f, " ({value:?})")?;
+        }
+
+        match self.error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => write!(f, ": {}", err.kind()),
+            csv::ErrorKind::UnequalLengths {
+                expected_len, len, ..
+            } => write!(f, ": expected {expected_len} columns, found {len}"),
+            _ => write!(f, ": {}", self.error),
+        }
+    }
+}
+
+/// The records found for a session, along with any rows which could not be read.
+type Rows<T> = (Vec<T>, Vec<RowError>);
+
+/// The file names we look for within a bundle, each of which may have a `.gz` or `.zst` extension.
+const SESSIONS_CSV: &str = "sessions.csv";
+const EVENTS_CSV: &str = "events.csv";
+const COMPRESSED_EXTENSIONS: [&str; 3] = ["", ".gz", ".zst"];
+
 impl<'a> CsvSource<'a> {
+    /// Reads the session and its events, failing if any of the rows that might belong to the session can't be read.
     pub fn get_data(&self) -> Result<(SessionRecord, Vec<EventRecord>), CsvParsingError> {
-        let mut session_deserialization_errors = Vec::new();
-        let session_record = csv::Reader::from_reader(BufReader::new(File::open(self.sessions)?))
-            .deserialize::<SessionRecord>()
-            .filter_map(|record_res| {
-                record_res
-                    .map_err(|err| session_deserialization_errors.push(err))
-                    .ok()
-            })
-            .find(|record| record.session_id == self.session_id)
-            .ok_or(CsvParsingError::SessionNotFound(self.session_id))?;
-
-        if !session_deserialization_errors.is_empty() {
+        let ((mut session_records, session_errors), (event_records, event_errors)) =
+            self.get_rows()?;
+
+        if !session_errors.is_empty() {
             return Err(CsvParsingError::SessionDeserializationErrors(
-                session_deserialization_errors,
+                session_errors,
             ));
         }
 
-        let mut event_deserialization_errors = Vec::new();
-        let event_records: Vec<EventRecord> =
-            csv::Reader::from_reader(BufReader::new(File::open(self.events)?))
-                .deserialize::<EventRecord>()
-                .filter_map(|record_res| {
-                    record_res
-                        .map_err(|err| event_deserialization_errors.push(err))
-                        .ok()
-                })
-                .filter(|record| record.session_id == self.session_id)
-                .collect();
-
-        if !event_deserialization_errors.is_empty() {
-            return Err(CsvParsingError::EventDeserializationErrors(
-                event_deserialization_errors,
-            ));
+        let session_record = session_records
+            .pop()
+            .ok_or(CsvParsingError::SessionNotFound(
+                self.session_id.unwrap_or_default(),
+            ))?;
+
+        if !event_errors.is_empty() {
+            return Err(CsvParsingError::EventDeserializationErrors(event_errors));
         }
 
         Ok((session_record, event_records))
     }
+
+    /// Reads the session and its events, skipping any rows that can't be read.
+    ///
+    /// The rows that were skipped are returned alongside the data, so that they can be reported as warnings.
+    pub fn get_data_lenient(
+        &self,
+    ) -> Result<(SessionRecord, Vec<EventRecord>, Vec<RowError>), CsvParsingError> {
+        let ((mut session_records, mut skipped), (event_records, event_errors)) =
+            self.get_rows()?;
+        skipped.extend(event_errors);
+
+        let session_record = session_records
+            .pop()
+            .ok_or(CsvParsingError::SessionNotFound(
+                self.session_id.unwrap_or_default(),
+            ))?;
+
+        Ok((session_record, event_records, skipped))
+    }
+
+    /// Reads every session and its events, in the order of the sessions CSV, failing if any of the rows can't be
+    /// read.
+    ///
+    /// Events that don't belong to any of the sessions are left out.
+    pub fn get_all_data(&self) -> Result<Vec<(SessionRecord, Vec<EventRecord>)>, CsvParsingError> {
+        let ((session_records, session_errors), (event_records, event_errors)) = self.get_rows()?;
+
+        if !session_errors.is_empty() {
+            return Err(CsvParsingError::SessionDeserializationErrors(
+                session_errors,
+            ));
+        }
+        if !event_errors.is_empty() {
+            return Err(CsvParsingError::EventDeserializationErrors(event_errors));
+        }
+
+        Ok(group_by_session(session_records, event_records))
+    }
+
+    /// Reads every session and its events, skipping any rows that can't be read; see [CsvSource::get_all_data].
+    #[allow(clippy::type_complexity)]
+    pub fn get_all_data_lenient(
+        &self,
+    ) -> Result<(Vec<(SessionRecord, Vec<EventRecord>)>, Vec<RowError>), CsvParsingError> {
+        let ((session_records, mut skipped), (event_records, event_errors)) = self.get_rows()?;
+        skipped.extend(event_errors);
+
+        Ok((group_by_session(session_records, event_records), skipped))
+    }
+
+    /// Reads the entries of an export of the slow query log, `system_traces.node_slow_log`, that refer to the session,
+    /// or every entry when reading every session, failing if any of the rows can't be read.
+    ///
+    /// The slow log is expected to have been exported in the same format as the sessions and events.
+    pub fn get_slow_log(&self, slow_log: &Path) -> Result<Vec<SlowLogRecord>, CsvParsingError> {
+        let mut reader = self.format.reader(input::open(slow_log)?);
+        let (records, errors) = self.read_matching(&mut reader, None, None)?;
+
+        if !errors.is_empty() {
+            return Err(CsvParsingError::SlowLogDeserializationErrors(errors));
+        }
+
+        Ok(records)
+    }
+
+    fn get_rows(&self) -> Result<(Rows<SessionRecord>, Rows<EventRecord>), CsvParsingError> {
+        match self.input {
+            CsvInput::Pair { sessions, events } => {
+                if input::is_stdin(sessions) && input::is_stdin(events) {
+                    return Err(CsvParsingError::BothFromStdin);
+                }
+
+                let session_rows = self.read_session(input::open(sessions)?)?;
+                let event_rows = self.read_events_from(events)?;
+                Ok((session_rows, event_rows))
+            }
+            CsvInput::Bundle(dir) if dir.is_dir() => {
+                let sessions = find_in_dir(dir, SESSIONS_CSV)?;
+                let events = find_in_dir(dir, EVENTS_CSV)?;
+
+                let session_rows = self.read_session(input::open(&sessions)?)?;
+                let event_rows = self.read_events_from(&events)?;
+                Ok((session_rows, event_rows))
+            }
+            CsvInput::Bundle(tarball) => self.read_tarball(input::open(tarball)?),
+        }
+    }
+
+    /// Reads the entries of the tarball in whatever order they were archived in.
+    fn read_tarball(
+        &self,
+        reader: impl Read,
+    ) -> Result<(Rows<SessionRecord>, Rows<EventRecord>), CsvParsingError> {
+        let mut session_rows = None;
+        let mut event_rows = None;
+
+        for entry in tar::Archive::new(reader).entries()? {
+            let entry = entry?;
+            let path = entry.path()?;
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+
+            if is_bundle_file(file_name, SESSIONS_CSV) && session_rows.is_none() {
+                session_rows = Some(self.read_session(input::decompress(entry)?)?);
+            } else if is_bundle_file(file_name, EVENTS_CSV) && event_rows.is_none() {
+                event_rows = Some(self.read_events(input::decompress(entry)?)?);
+            }
+        }
+
+        Ok((
+            session_rows.ok_or(CsvParsingError::MissingFromBundle(SESSIONS_CSV))?,
+            event_rows.ok_or(CsvParsingError::MissingFromBundle(EVENTS_CSV))?,
+        ))
+    }
+
+    fn read_session(&self, reader: impl Read) -> Result<Rows<SessionRecord>, CsvParsingError> {
+        let mut reader = self.format.reader(reader);
+        let limit = self.session_id.map(|_| 1);
+        self.read_matching(&mut reader, None, limit)
+    }
+
+    /// Reads the events from the file at the given path, making use of the index if possible.
+    ///
+    /// The index relies on seeking within the file, so it is not used for stdin or compressed files.
+    fn read_events_from(&self, path: &Path) -> Result<Rows<EventRecord>, CsvParsingError> {
+        let session_id = match self.session_id {
+            Some(session_id) if self.use_index => session_id,
+            _ => return self.read_events(input::open(path)?),
+        };
+        if input::is_stdin(path) || input::is_compressed(path)? {
+            return self.read_events(input::open(path)?);
+        }
+
+        let index = EventsIndex::load_or_build(path, &self.format)?;
+        let mut reader = self.format.reader(std::fs::File::open(path)?);
+
+        let mut event_records = Vec::new();
+        let mut event_errors = Vec::new();
+        for entry in index.get(&session_id) {
+            reader
+                .seek(entry.position())
+                .map_err(CsvParsingError::Index)?;
+            let (records, errors) = self.read_matching(&mut reader, Some(entry.end), None)?;
+            event_records.extend(records);
+            event_errors.extend(errors);
+        }
+
+        Ok((event_records, event_errors))
+    }
+
+    fn read_events(&self, reader: impl Read) -> Result<Rows<EventRecord>, CsvParsingError> {
+        let mut reader = self.format.reader(reader);
+        self.read_matching(&mut reader, None, None)
+    }
+
+    /// Streams the rows of an exported CSV, only deserializing those belonging to the session, or every row when
+    /// reading every session.
+    ///
+    /// The rows of a session are contiguous in a `COPY TO` export when `session_id` is the partition key, so unless
+    /// [CsvSource::with_full_scan] is set we stop reading at the first row after the session's rows; see
+    /// [ExportedRecord::CONTIGUOUS]. Reading also stops at the byte offset `end`, or once `limit` rows have been found.
+    ///
+    /// Rows that can't be split into the right number of columns are only reported if they might belong to the
+    /// session.
+    fn read_matching<T: ExportedRecord>(
+        &self,
+        reader: &mut csv::Reader<impl Read>,
+        end: Option<u64>,
+        limit: Option<usize>,
+    ) -> Result<Rows<T>, CsvParsingError> {
+        let mut headers = self
+            .format
+            .headers(reader)
+            .map_err(CsvParsingError::Headers)?;
+        let column = self
+            .format
+            .session_id_column(headers.as_ref())
+            .ok_or(CsvParsingError::MissingColumn)?;
+        let session_id = |record: &csv::ByteRecord| {
+            record
+                .get(column)
+                .and_then(|id| Uuid::try_parse_ascii(id).ok())
+        };
+        let wanted = |id: Option<Uuid>| self.session_id.is_none() || id == self.session_id;
+
+        let mut records = Vec::new();
+        let mut errors = Vec::new();
+        let mut record = csv::ByteRecord::new();
+        let mut seen = false;
+
+        while end.is_none_or(|end| reader.position().byte() < end)
+            && limit.is_none_or(|limit| records.len() < limit)
+        {
+            match reader.read_byte_record(&mut record) {
+                Ok(false) => break,
+                Ok(true) => {}
+                Err(err) if err.is_io_error() => return Err(std::io::Error::from(err).into()),
+                Err(err) => {
+                    let id = session_id(&record);
+                    if id.is_none() || wanted(id) {
+                        errors.push(RowError::new(err, headers.as_ref(), &record));
+                    }
+                    continue;
+                }
+            }
+
+            // A row whose session id can't be read might be the session's, so it's deserialized to report the error
+            let id = session_id(&record);
+            if id.is_some() && !wanted(id) {
+                if seen && T::CONTIGUOUS && !self.full_scan {
+                    break;
+                }
+                continue;
+            }
+            seen |= id.is_some();
+
+            if headers.is_none() {
+                headers = T::default_headers(record.len());
+            }
+            if let Some(null) = &self.format.null {
+                let position = record.position().cloned();
+                record = record
+                    .iter()
+                    .map(|field| if field == null.as_bytes() { &[] } else { field })
+                    .collect();
+                record.set_position(position);
+            }
+
+            match record.deserialize(headers.as_ref()) {
+                Ok(deserialized) => records.push(deserialized),
+                Err(err) => errors.push(RowError::new(err, headers.as_ref(), &record)),
+            }
+        }
+
+        Ok((records, errors))
+    }
+}
+
+/// Pairs each session with its events, keeping the order of the sessions.
+fn group_by_session(
+    session_records: Vec<SessionRecord>,
+    event_records: Vec<EventRecord>,
+) -> Vec<(SessionRecord, Vec<EventRecord>)> {
+    let mut events: HashMap<Uuid, Vec<EventRecord>> = HashMap::new();
+    for event_record in event_records {
+        events
+            .entry(event_record.session_id)
+            .or_default()
+            .push(event_record);
+    }
+
+    session_records
+        .into_iter()
+        .map(|session_record| {
+            let session_events = events
+                .remove(&session_record.session_id)
+                .unwrap_or_default();
+            (session_record, session_events)
+        })
+        .collect()
+}
+
+/// Finds the file with the given name within the directory, allowing for a compressed extension.
+fn find_in_dir(dir: &Path, name: &'static str) -> Result<PathBuf, CsvParsingError> {
+    COMPRESSED_EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{name}{extension}")))
+        .find(|path| path.is_file())
+        .ok_or(CsvParsingError::MissingFromBundle(name))
+}
+
+fn is_bundle_file(file_name: &str, name: &str) -> bool {
+    COMPRESSED_EXTENSIONS
+        .iter()
+        .any(|extension| file_name == format!("{name}{extension}"))
 }
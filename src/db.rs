@@ -1,11 +1,17 @@
 use crate::{
-    records::{EventRecord, SessionRecord},
-    SpanId,
+    records::{EventRecord, SessionRecord, SlowLogRecord},
+    DbDriverOptions, SpanId,
 };
-use chrono::{LocalResult, TimeZone, Utc};
-use scylla::{query::Query, statement::Consistency, FromRow};
-use std::{collections::HashMap, net::IpAddr, net::SocketAddr};
+use chrono::{DateTime, Duration, DurationRound, LocalResult, TimeZone, Utc};
+use scylla::{
+    frame::{response::result::Row, value::ValueList},
+    query::Query,
+    statement::Consistency,
+    FromRow,
+};
+use std::{collections::HashMap, future::Future, net::IpAddr, net::SocketAddr, sync::Arc};
 use thiserror::Error;
+use tokio::task::JoinSet;
 use uuid::Uuid;
 
 /// Makes it much easier to use [scylla::FromRow] for the very large `system_traces.sessions` rows.
@@ -26,22 +32,62 @@ pub type DbSessionRecord = (
 /// Makes it much easier to use [scylla::FromRow] for the very large `system_traces.events` rows.
 pub type DbEventRecord = (Uuid, Uuid, String, IpAddr, i32, String, i64, i64);
 
-/// A source for the data based on an exported CSV.
-#[derive(Debug)]
-pub struct DbSource {
-    addr: SocketAddr,
-    session_id: Uuid,
+/// Makes it much easier to use [scylla::FromRow] for the very large `system_traces.node_slow_log` rows.
+pub type DbSlowLogRecord = (
+    Uuid,
+    IpAddr,
+    i32,
+    String,
+    i64,
+    i32,
+    Option<HashMap<String, String>>,
+    Uuid,
+    IpAddr,
+    Option<Vec<String>>,
+    Option<String>,
+);
+
+/// How requests are made to the database.
+#[derive(Debug, Clone)]
+pub struct DbConfig {
+    pub consistency: Consistency,
+    /// How long to wait for each request, and for each connection to be made. The driver's defaults are used if not
+    /// set
+    pub timeout: Option<std::time::Duration>,
+    /// How many rows to fetch at a time
+    pub page_size: i32,
 }
 
-impl DbSource {
-    pub fn new(addr: impl Into<SocketAddr>, session_id: Uuid) -> Self {
+impl Default for DbConfig {
+    fn default() -> Self {
+        Self {
+            consistency: Consistency::One,
+            timeout: None,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl From<&DbDriverOptions> for DbConfig {
+    fn from(options: &DbDriverOptions) -> Self {
         Self {
-            addr: addr.into(),
-            session_id,
+            consistency: *options.consistency,
+            timeout: options.timeout.map(std::time::Duration::from_secs),
+            page_size: *options.page_size,
         }
     }
 }
 
+/// A source for the data based on a live database.
+///
+/// The driver keeps a pool of connections to every node, which is shared between clones of the source, so one source
+/// can be used for any number of sessions.
+#[derive(Debug, Clone)]
+pub struct DbSource {
+    conn: Arc<scylla::Session>,
+    config: DbConfig,
+}
+
 /// The kinds of errors that can be experienced while parsing the data from the DB.
 ///
 /// These are pretty much all just wrapping errors from [scylla].
@@ -61,58 +107,206 @@ pub enum DbParsingError {
 
     #[error("there was an issue parsing the data from the returned row: {0}")]
     FromRow(#[from] scylla::cql_to_rust::FromRowError),
+
+    #[error("one of the concurrent requests failed to complete: {0}")]
+    Join(#[from] tokio::task::JoinError),
+
+    #[error("the provided session id {0} could not be found")]
+    SessionNotFound(Uuid),
+
+    #[error("the timestamp {0} is out of the range that can be represented")]
+    Timestamp(i64),
 }
 
 impl DbSource {
-    pub async fn get_data(&self) -> Result<(SessionRecord, Vec<EventRecord>), DbParsingError> {
-        let conn = scylla::SessionBuilder::new()
-            .known_node_addr(self.addr)
-            .build()
+    /// Connects to the cluster of the node at the given address.
+    pub async fn connect(
+        addr: impl Into<SocketAddr>,
+        config: DbConfig,
+    ) -> Result<Self, DbParsingError> {
+        let mut builder = scylla::SessionBuilder::new().known_node_addr(addr.into());
+        if let Some(timeout) = config.timeout {
+            builder = builder.connection_timeout(timeout);
+        }
+
+        Ok(Self {
+            conn: Arc::new(builder.build().await?),
+            config,
+        })
+    }
+
+    /// Reads the session and its events, with both requests in flight at once.
+    pub async fn get_data(
+        &self,
+        session_id: Uuid,
+    ) -> Result<(SessionRecord, Vec<EventRecord>), DbParsingError> {
+        let (session_record, event_records) =
+            tokio::try_join!(self.get_session(session_id), self.get_events(session_id))?;
+        let session_record = session_record.ok_or(DbParsingError::SessionNotFound(session_id))?;
+
+        Ok((session_record, event_records))
+    }
+
+    /// Reads each of the sessions and their events, a few sessions at a time, in the order given; see
+    /// [DbSource::get_data].
+    pub async fn get_many(
+        &self,
+        session_ids: Vec<Uuid>,
+    ) -> Result<Vec<(SessionRecord, Vec<EventRecord>)>, DbParsingError> {
+        concurrently(session_ids, |session_id| {
+            let source = self.clone();
+            async move { source.get_data(session_id).await }
+        })
+        .await
+    }
+
+    /// Reads each of the sessions and their events, a few sessions at a time, leaving out any that aren't there.
+    async fn get_existing(
+        &self,
+        session_ids: Vec<Uuid>,
+    ) -> Result<Vec<(SessionRecord, Vec<EventRecord>)>, DbParsingError> {
+        let sessions = concurrently(session_ids, |session_id| {
+            let source = self.clone();
+            async move {
+                match source.get_data(session_id).await {
+                    Ok(session) => Ok(Some(session)),
+                    Err(DbParsingError::SessionNotFound(_)) => Ok(None),
+                    Err(err) => Err(err),
+                }
+            }
+        })
+        .await?;
+
+        Ok(sessions.into_iter().flatten().collect())
+    }
+
+    /// A request with the configuration applied.
+    fn query(&self, query: impl Into<Query>) -> Query {
+        let mut query = query.into();
+        query.set_consistency(self.config.consistency);
+        query.set_request_timeout(self.config.timeout);
+        // The driver panics on a page size that isn't positive
+        query.set_page_size(self.config.page_size.max(1));
+        query
+    }
+
+    /// Reads every row of a query, a page at a time.
+    async fn query_all(
+        &self,
+        query: impl Into<Query>,
+        values: impl ValueList + Clone,
+    ) -> Result<Vec<Row>, DbParsingError> {
+        let query = self.query(query);
+
+        let mut rows = Vec::new();
+        let mut paging_state = None;
+        loop {
+            let result = self
+                .conn
+                .query_paged(query.clone(), values.clone(), paging_state)
+                .await?;
+            paging_state = result.paging_state.clone();
+            rows.extend(result.rows_or_empty());
+
+            if paging_state.is_none() {
+                break;
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Reads the ids of the sessions started within the window from one of the time indexes, oldest first.
+    async fn read_time_idx(
+        &self,
+        index: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, DbParsingError> {
+        let index_records: Vec<DbTimeIdxRecord> = self
+            .read_minutes(TIME_IDX_QUERY, index, since, until)
             .await?;
+        Ok(index_records.into_iter().map(|(_, id)| id).collect())
+    }
 
-        let mut session_query = Query::from("SELECT session_id, client, command, coordinator, duration, parameters, request, started_at, request_size, response_size, username FROM system_traces.sessions WHERE session_id=?");
-        session_query.set_consistency(Consistency::One);
-        let (
-            session_id,
-            client,
-            command,
-            coordinator,
-            duration,
-            parameters,
-            request,
-            started_at,
-            request_size,
-            response_size,
-            username,
-        ): DbSessionRecord = <_>::from_row(
-            conn.query(session_query, (self.session_id,))
-                .await?
-                .first_row()?,
-        )?;
-
-        let started_at = match Utc.timestamp_millis_opt(started_at) {
-            LocalResult::Single(datetime) => datetime,
-            _ => todo!(),
-        };
+    /// Reads the rows of one of the time indexes for the sessions started within the window, oldest first, selecting
+    /// the columns with `select`.
+    ///
+    /// The index is partitioned by the minute the sessions started in, so it's read a minute at a time; see
+    /// [minutes].
+    async fn read_minutes<T: FromRow + Send + 'static>(
+        &self,
+        select: &str,
+        index: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<T>, DbParsingError> {
+        let query = format!(
+            "{select} FROM system_traces.{index} WHERE minute = ? AND started_at >= ? AND started_at < ?"
+        );
+        let buckets = concurrently(minutes(since, until), |minute| {
+            let source = self.clone();
+            let query = query.clone();
+            async move {
+                let mut index_records = Vec::new();
+                for row in source.query_all(query, (minute, since, until)).await? {
+                    index_records.push(T::from_row(row)?);
+                }
+                Ok(index_records)
+            }
+        })
+        .await?;
 
-        let session_record = SessionRecord {
-            session_id,
-            client,
-            command,
-            coordinator,
-            duration,
-            parameters: format!("{:?}", parameters),
-            request,
-            started_at,
-            request_size: Some(request_size as u32),
-            response_size: Some(response_size as u32),
-            username: Some(username),
-        };
+        // Each minute is already in order, and so are the minutes
+        Ok(buckets.into_iter().flatten().collect())
+    }
 
-        let mut event_query = Query::from(
-            "SELECT session_id, event_id, activity, source, source_elapsed, thread, scylla_parent_id, scylla_span_id FROM system_traces.events WHERE session_id=?");
-        event_query.set_consistency(Consistency::One);
-        let rows = conn.query(event_query, (self.session_id,)).await?.rows()?;
+    /// Reads a single entry of the slow query log, if it's still there.
+    async fn get_slow_log_entry(
+        &self,
+        (start_time, node_ip, shard): DbSlowLogKey,
+    ) -> Result<Option<SlowLogRecord>, DbParsingError> {
+        let row = self
+            .conn
+            .query(
+                self.query(format!(
+                    "{SLOW_LOG_QUERY} WHERE start_time = ? AND node_ip = ? AND shard = ?"
+                )),
+                (start_time, node_ip, shard),
+            )
+            .await?
+            .maybe_first_row()?;
+
+        Ok(match row {
+            Some(row) => Some(slow_log_record(<_>::from_row(row)?)?),
+            None => None,
+        })
+    }
+
+    /// Reads a single session, if it's still there.
+    async fn get_session(&self, session_id: Uuid) -> Result<Option<SessionRecord>, DbParsingError> {
+        let row = self
+            .conn
+            .query(
+                self.query(format!("{SESSION_QUERY} WHERE session_id=?")),
+                (session_id,),
+            )
+            .await?
+            .maybe_first_row()?;
+
+        Ok(match row {
+            Some(row) => Some(session_record(<_>::from_row(row)?)?),
+            None => None,
+        })
+    }
+
+    /// Reads the events of a session, a page at a time, so that sessions with very many events don't have to come
+    /// back in a single response.
+    async fn get_events(&self, session_id: Uuid) -> Result<Vec<EventRecord>, DbParsingError> {
+        let rows = self.query_all(
+            "SELECT session_id, event_id, activity, source, source_elapsed, thread, scylla_parent_id, scylla_span_id FROM system_traces.events WHERE session_id=?",
+            (session_id,),
+        ).await?;
 
         let mut event_records = vec![];
         for row in rows {
@@ -138,6 +332,401 @@ impl DbSource {
             })
         }
 
-        Ok((session_record, event_records))
+        Ok(event_records)
+    }
+}
+
+/// A source for every session started within a window of time, based on a live database.
+#[derive(Debug)]
+pub struct DbWindowSource {
+    source: DbSource,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl DbWindowSource {
+    /// Sessions started at or after `since`, and before `until`; either end may be left open.
+    pub fn new(
+        source: DbSource,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            source,
+            since,
+            until,
+        }
+    }
+
+    /// Reads every session within the window and its events, oldest first.
+    ///
+    /// The sessions are found with `system_traces.sessions_time_idx`, which Scylla buckets by the minute the session
+    /// started in, so only the minutes within the window are read. Without a start to the window there are no
+    /// minutes to go by, so the whole index is scanned instead. Each session and its events are then fetched with a
+    /// few requests in flight at once.
+    pub async fn get_all_data(
+        &self,
+    ) -> Result<Vec<(SessionRecord, Vec<EventRecord>)>, DbParsingError> {
+        let session_ids = match self.since {
+            Some(since) => {
+                let until = self.until.unwrap_or_else(Utc::now);
+                self.source
+                    .read_time_idx(SESSIONS_TIME_IDX, since, until)
+                    .await?
+            }
+            None => {
+                let query = format!("{TIME_IDX_QUERY} FROM system_traces.{SESSIONS_TIME_IDX}");
+                let mut index_records = Vec::new();
+                for row in self.source.query_all(query, ()).await? {
+                    let index_record: DbTimeIdxRecord = <_>::from_row(row)?;
+                    if self.contains(index_record.0) {
+                        index_records.push(index_record);
+                    }
+                }
+                index_records.sort();
+                index_records.into_iter().map(|(_, id)| id).collect()
+            }
+        };
+
+        // The index can outlive the sessions it points at, as each row has its own TTL
+        self.source.get_existing(session_ids).await
+    }
+
+    fn contains(&self, started_at: DateTime<Utc>) -> bool {
+        contains(self.since, self.until, started_at)
+    }
+}
+
+/// A source for the sessions written to a live database while we watch, read a poll at a time; see
+/// [DbFollowSource::poll].
+#[derive(Debug)]
+pub struct DbFollowSource {
+    source: DbSource,
+    /// The index to look for new sessions in
+    index: &'static str,
+    /// When we started following, which polls never look back past
+    started: DateTime<Utc>,
+    /// Where the next poll reads from, before looking back
+    since: DateTime<Utc>,
+    /// The sessions that have already been read, and when they started
+    seen: HashMap<Uuid, DateTime<Utc>>,
+}
+
+impl DbFollowSource {
+    /// Follows the sessions started from now on, or only those written to the slow query log.
+    pub fn new(source: DbSource, slow_log: bool) -> Self {
+        let now = Utc::now();
+        Self {
+            source,
+            index: match slow_log {
+                true => SLOW_LOG_TIME_IDX,
+                false => SESSIONS_TIME_IDX,
+            },
+            started: now,
+            since: now,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Reads the sessions that have been written since the last poll and their events, oldest first.
+    ///
+    /// Sessions are only written once they've finished, so each poll looks back a little way for any that have turned
+    /// up late, skipping the ones that have already been read; see [FOLLOW_LOOK_BACK_SECS]. Sessions that take longer
+    /// than that to be written are missed. Sessions started before we began following are never read.
+    ///
+    /// If a poll fails, the next one picks up from where it would have.
+    pub async fn poll(&mut self) -> Result<Vec<(SessionRecord, Vec<EventRecord>)>, DbParsingError> {
+        let now = Utc::now();
+        let since = (self.since - Duration::seconds(FOLLOW_LOOK_BACK_SECS)).max(self.started);
+
+        let session_ids = self
+            .source
+            .read_time_idx(self.index, since, now)
+            .await?
+            .into_iter()
+            .filter(|session_id| !self.seen.contains_key(session_id))
+            .collect();
+        let sessions = self.source.get_existing(session_ids).await?;
+
+        for (session_record, _) in &sessions {
+            self.seen
+                .insert(session_record.session_id, session_record.started_at);
+        }
+        // Anything older can't be read again
+        self.seen.retain(|_, started_at| *started_at >= since);
+        self.since = now;
+
+        Ok(sessions)
+    }
+}
+
+/// A source for the entries of the slow query log within a window of time, based on a live database.
+#[derive(Debug)]
+pub struct DbSlowLogSource {
+    source: DbSource,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl DbSlowLogSource {
+    /// Entries for queries started at or after `since`, and before `until`; either end may be left open.
+    pub fn new(
+        source: DbSource,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            source,
+            since,
+            until,
+        }
+    }
+
+    /// Reads every entry of `system_traces.node_slow_log` within the window, oldest first.
+    ///
+    /// The entries are found with `system_traces.node_slow_log_time_idx`, a minute at a time, the same way as
+    /// [DbWindowSource::get_all_data], and are then fetched a few at a time. Without a start to the window there are
+    /// no minutes to go by, so the whole table is scanned instead, a page at a time.
+    pub async fn get_entries(&self) -> Result<Vec<SlowLogRecord>, DbParsingError> {
+        let slow_log_records = match self.since {
+            Some(since) => {
+                let until = self.until.unwrap_or_else(Utc::now);
+                let keys = self
+                    .source
+                    .read_minutes(SLOW_LOG_TIME_IDX_QUERY, SLOW_LOG_TIME_IDX, since, until)
+                    .await?;
+                let entries = concurrently(keys, |key| {
+                    let source = self.source.clone();
+                    async move { source.get_slow_log_entry(key).await }
+                })
+                .await?;
+                // The index can outlive the entries it points at, as each row has its own TTL
+                entries.into_iter().flatten().collect()
+            }
+            None => {
+                let mut slow_log_records = Vec::new();
+                for row in self.source.query_all(SLOW_LOG_QUERY, ()).await? {
+                    slow_log_records.push(slow_log_record(<_>::from_row(row)?)?);
+                }
+                slow_log_records
+            }
+        };
+
+        let mut slow_log_records: Vec<SlowLogRecord> = slow_log_records
+            .into_iter()
+            .filter(|slow_log_record| contains(self.since, self.until, slow_log_record.date))
+            .collect();
+        slow_log_records.sort_by_key(|slow_log_record| slow_log_record.date);
+
+        Ok(slow_log_records)
+    }
+}
+
+fn contains(since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>, at: DateTime<Utc>) -> bool {
+    since.is_none_or(|since| at >= since) && until.is_none_or(|until| at < until)
+}
+
+/// The `started_at` and `session_id` of a row of `system_traces.sessions_time_idx`.
+type DbTimeIdxRecord = (DateTime<Utc>, Uuid);
+/// The `start_time`, `node_ip` and `shard` of a row of `system_traces.node_slow_log_time_idx`, which together are the
+/// primary key of `system_traces.node_slow_log`.
+type DbSlowLogKey = (Uuid, IpAddr, i32);
+
+/// The columns of `system_traces.sessions`, in the order of [DbSessionRecord].
+const SESSION_QUERY: &str = "SELECT session_id, client, command, coordinator, duration, parameters, request, started_at, request_size, response_size, username FROM system_traces.sessions";
+/// The columns of `system_traces.node_slow_log`, in the order of [DbSlowLogRecord].
+const SLOW_LOG_QUERY: &str = "SELECT start_time, node_ip, shard, command, date, duration, parameters, session_id, source_ip, table_names, username FROM system_traces.node_slow_log";
+/// The columns of `system_traces.sessions_time_idx` and `system_traces.node_slow_log_time_idx`, in the order of
+/// [DbTimeIdxRecord].
+const TIME_IDX_QUERY: &str = "SELECT started_at, session_id";
+/// The columns of `system_traces.node_slow_log_time_idx` that point at an entry of the slow query log, in the order of
+/// [DbSlowLogKey].
+const SLOW_LOG_TIME_IDX_QUERY: &str = "SELECT start_time, node_ip, shard";
+/// The index of every session, by the minute they started in.
+const SESSIONS_TIME_IDX: &str = "sessions_time_idx";
+/// The index of the sessions written to the slow query log, by the minute they started in.
+const SLOW_LOG_TIME_IDX: &str = "node_slow_log_time_idx";
+/// How far back each poll of a [DbFollowSource] looks for sessions that were written late, in seconds.
+const FOLLOW_LOOK_BACK_SECS: i64 = 60;
+/// How many rows to fetch at a time, unless configured otherwise.
+pub const DEFAULT_PAGE_SIZE: i32 = 1000;
+/// How many requests to have in flight at once when fetching many minutes of the index, or many sessions.
+const CONCURRENT_REQUESTS: usize = 16;
+
+/// The minutes that the time indexes are partitioned by which a window covers, from the minute `since` falls in up to,
+/// but not including, `until`.
+fn minutes(since: DateTime<Utc>, until: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let mut minutes = Vec::new();
+    let mut minute = since.duration_trunc(Duration::minutes(1)).unwrap_or(since);
+    while minute < until {
+        minutes.push(minute);
+        minute += Duration::minutes(1);
+    }
+    minutes
+}
+
+/// Runs `fetch` for each of the items, with up to [CONCURRENT_REQUESTS] running at once, keeping the results in the
+/// order of the items. Fails with the first error to come back.
+async fn concurrently<I, T, F, Fut>(items: Vec<I>, fetch: F) -> Result<Vec<T>, DbParsingError>
+where
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = Result<T, DbParsingError>> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut results: Vec<Option<T>> = items.iter().map(|_| None).collect();
+    let mut tasks = JoinSet::new();
+
+    for (i, item) in items.into_iter().enumerate() {
+        if tasks.len() >= CONCURRENT_REQUESTS {
+            if let Some(joined) = tasks.join_next().await {
+                let (i, result) = joined?;
+                results[i] = Some(result?);
+            }
+        }
+        let fetching = fetch(item);
+        tasks.spawn(async move { (i, fetching.await) });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        let (i, result) = joined?;
+        results[i] = Some(result?);
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+fn session_record(
+    (
+        session_id,
+        client,
+        command,
+        coordinator,
+        duration,
+        parameters,
+        request,
+        started_at,
+        request_size,
+        response_size,
+        username,
+    ): DbSessionRecord,
+) -> Result<SessionRecord, DbParsingError> {
+    let started_at = timestamp(started_at)?;
+
+    Ok(SessionRecord {
+        session_id,
+        client,
+        command,
+        coordinator,
+        duration,
+        parameters: format!("{:?}", parameters),
+        request,
+        started_at,
+        request_size: Some(request_size as u32),
+        response_size: Some(response_size as u32),
+        username: Some(username),
+    })
+}
+
+fn slow_log_record(
+    (
+        start_time,
+        node_ip,
+        shard,
+        command,
+        date,
+        duration,
+        parameters,
+        session_id,
+        source_ip,
+        table_names,
+        username,
+    ): DbSlowLogRecord,
+) -> Result<SlowLogRecord, DbParsingError> {
+    let date = timestamp(date)?;
+    // Written the same way as cqlsh exports a set, so that entries read from either source look alike
+    let table_names = table_names
+        .unwrap_or_default()
+        .iter()
+        .map(|table_name| format!("'{table_name}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(SlowLogRecord {
+        start_time,
+        node_ip,
+        shard,
+        command,
+        date,
+        duration,
+        parameters: format!("{:?}", parameters.unwrap_or_default()),
+        session_id,
+        source_ip,
+        table_names: format!("{{{table_names}}}"),
+        username,
+    })
+}
+
+/// Reads a timestamp given in milliseconds since the epoch.
+fn timestamp(millis: i64) -> Result<DateTime<Utc>, DbParsingError> {
+    match Utc.timestamp_millis_opt(millis) {
+        LocalResult::Single(datetime) => Ok(datetime),
+        _ => Err(DbParsingError::Timestamp(millis)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn minutes_of_a_window() {
+        // The minute that `since` falls in is read, but `until` is excluded
+        assert_eq!(
+            minutes(at("2023-08-13T01:48:30Z"), at("2023-08-13T01:50:00Z")),
+            [at("2023-08-13T01:48:00Z"), at("2023-08-13T01:49:00Z")]
+        );
+        assert_eq!(
+            minutes(at("2023-08-13T01:48:00Z"), at("2023-08-13T01:50:00.001Z")),
+            [
+                at("2023-08-13T01:48:00Z"),
+                at("2023-08-13T01:49:00Z"),
+                at("2023-08-13T01:50:00Z")
+            ]
+        );
+        assert_eq!(
+            minutes(at("2023-08-13T01:48:59Z"), at("2023-08-13T01:49:00Z")),
+            [at("2023-08-13T01:48:00Z")]
+        );
+        assert!(minutes(at("2023-08-13T01:49:00Z"), at("2023-08-13T01:49:00Z")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrently_keeps_the_order() {
+        // More items than can be in flight at once, finishing in the reverse order that they were started
+        let items: Vec<u64> = (0..CONCURRENT_REQUESTS as u64 * 2).collect();
+        let results = concurrently(items.clone(), |item| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(40 - item)).await;
+            Ok(item)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(results, items);
+    }
+
+    #[tokio::test]
+    async fn concurrently_fails_with_an_error() {
+        let result = concurrently(vec![1, 2, 3], |item| async move {
+            match item {
+                2 => Err(DbParsingError::SessionNotFound(Uuid::nil())),
+                _ => Ok(item),
+            }
+        })
+        .await;
+
+        assert!(matches!(result, Err(DbParsingError::SessionNotFound(_))));
     }
 }
@@ -0,0 +1,272 @@
+use crate::{
+    cli::OutputFormat,
+    color::{self, BOLD, DIM, SLOW},
+    event::{format_duration, tree_str},
+    Cli, Event, Session,
+};
+use serde::Serialize;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// Two traces of the same query, lined up event by event; see [Session::diff].
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SessionDiff {
+    pub before: Uuid,
+    pub after: Uuid,
+    pub total_before: i64,
+    pub total_after: i64,
+    /// The events of both sessions, in the depth-first order of [Session::events]
+    pub rows: Vec<DiffRow>,
+}
+
+/// How an event of one session lines up with the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    /// The event is in both sessions
+    Matched,
+    /// The event is only in the second session
+    Added,
+    /// The event is only in the first session
+    Missing,
+}
+
+/// A single row of a [SessionDiff].
+///
+/// The node and activity are those of the second session's event, unless it is missing from the second session.
+/// Durations are of the events alone, not including their children, in microseconds.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DiffRow {
+    pub change: Change,
+    pub depth: usize,
+    pub source: IpAddr,
+    pub activity: String,
+    pub before: Option<i64>,
+    pub after: Option<i64>,
+    #[serde(skip)]
+    is_parent: bool,
+}
+
+impl DiffRow {
+    fn new(change: Change, event: &Event, depth: usize) -> Self {
+        Self {
+            change,
+            depth,
+            source: event.source,
+            activity: event.activity.clone(),
+            before: None,
+            after: None,
+            is_parent: event.is_parent(),
+        }
+    }
+
+    /// How much longer the event took in the second session, if it is in both.
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.after? - self.before?)
+    }
+}
+
+impl SessionDiff {
+    /// How much longer the second session took than the first.
+    pub fn total_delta(&self) -> i64 {
+        self.total_after - self.total_before
+    }
+
+    /// Writes the rows of the diff, marking events that were added with `+` and those that are missing with `-`, and
+    /// finishing with the change in total duration.
+    pub fn display(&self, cli: &Cli, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        if let OutputFormat::Json = cli.format {
+            serde_json::to_writer_pretty(&mut *w, self)?;
+            return writeln!(w);
+        }
+
+        let color = cli.color.enabled();
+        let max_depth = self.rows.iter().map(|row| row.depth).max().unwrap_or(0);
+        let time = |micros: Option<i64>| {
+            micros
+                .map(|micros| format_duration(cli, micros))
+                .unwrap_or_else(|| String::from("-"))
+        };
+
+        writeln!(
+            w,
+            "{}",
+            color::paint(
+                &format!("Session ID: {} -> {}", self.before, self.after),
+                BOLD,
+                color
+            )
+        )?;
+
+        writeln!(w)?;
+        let headers = format!(
+            "  {:>10} {:>10} {:>10} {:15} activity",
+            "before", "after", "delta", "node"
+        );
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+
+        for row in &self.rows {
+            let marker = match row.change {
+                Change::Matched => ' ',
+                Change::Added => '+',
+                Change::Missing => '-',
+            };
+            let delta = row.delta().map(|delta| signed_duration(cli, delta));
+            let line = format!(
+                "{marker} {:>10} {:>10} {:>10} {:15} {} {}",
+                time(row.before),
+                time(row.after),
+                delta.unwrap_or_default(),
+                row.source.to_string(),
+                tree_str(cli, row.depth, max_depth, row.is_parent),
+                row.activity,
+            );
+
+            let slow = match (cli.slow_threshold, row.delta(), row.after) {
+                (Some(threshold), Some(delta), _) => delta >= threshold,
+                (Some(threshold), None, Some(after)) => after >= threshold,
+                _ => false,
+            };
+            let line = match row.change {
+                _ if slow => color::paint(&line, SLOW, color),
+                Change::Missing => color::paint(&line, DIM, color),
+                _ => line,
+            };
+            writeln!(w, "{line}")?;
+        }
+
+        writeln!(w)?;
+        writeln!(
+            w,
+            "Total Duration: {} -> {} ({})",
+            format_duration(cli, self.total_before),
+            format_duration(cli, self.total_after),
+            signed_duration(cli, self.total_delta()),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Session {
+    /// Lines up the events of this session with those of another trace of the same query.
+    ///
+    /// Events are matched by their [normalised activity][Event::normalised_activity], their depth within the tree,
+    /// and whether they happened on the coordinator, so that runs against different nodes or partitions still line
+    /// up. The alignment is the longest common subsequence of the two lists of events; when an event could be either
+    /// missing or added, the missing event comes first. For very large traces the events that differ are not lined
+    /// up, and are shown as missing and then added.
+    pub fn diff(&self, other: &Session) -> SessionDiff {
+        let before = self.events();
+        let after = other.events();
+        let key = |session: &Session, (event, depth): &(&Event, usize)| {
+            (
+                *depth,
+                event.source == session.coordinator,
+                event.normalised_activity(),
+            )
+        };
+        let before_keys: Vec<_> = before.iter().map(|e| key(self, e)).collect();
+        let after_keys: Vec<_> = after.iter().map(|e| key(other, e)).collect();
+
+        // Traces of the same query mostly differ in the middle, so only that needs the full comparison
+        let prefix = before_keys
+            .iter()
+            .zip(&after_keys)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = before_keys[prefix..]
+            .iter()
+            .rev()
+            .zip(after_keys[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let a = &before_keys[prefix..before_keys.len() - suffix];
+        let b = &after_keys[prefix..after_keys.len() - suffix];
+
+        // lcs[i][j] is the length of the longest common subsequence of a[i..] and b[j..]. The table grows with the
+        // product of the lengths, so past a point the middle is just shown as missing and then added
+        let lcs = (a.len().saturating_mul(b.len()) <= MAX_LCS_CELLS).then(|| {
+            let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+            for i in (0..a.len()).rev() {
+                for j in (0..b.len()).rev() {
+                    lcs[i][j] = match a[i] == b[j] {
+                        true => lcs[i + 1][j + 1] + 1,
+                        false => lcs[i + 1][j].max(lcs[i][j + 1]),
+                    };
+                }
+            }
+            lcs
+        });
+        let missing_first = |i: usize, j: usize| match &lcs {
+            Some(lcs) => lcs[i + 1][j] >= lcs[i][j + 1],
+            None => true,
+        };
+
+        let mut changes = vec![Change::Matched; prefix];
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                changes.push(Change::Matched);
+                i += 1;
+                j += 1;
+            } else if j == b.len() || (i < a.len() && missing_first(i, j)) {
+                changes.push(Change::Missing);
+                i += 1;
+            } else {
+                changes.push(Change::Added);
+                j += 1;
+            }
+        }
+        changes.extend(std::iter::repeat_n(Change::Matched, suffix));
+
+        let mut rows = Vec::with_capacity(changes.len());
+        let (mut before, mut after) = (before.into_iter(), after.into_iter());
+        for change in changes {
+            let row = match change {
+                Change::Matched => {
+                    let (a, _) = before.next().unwrap();
+                    let (b, depth) = after.next().unwrap();
+                    DiffRow {
+                        before: Some(a.durations().1),
+                        after: Some(b.durations().1),
+                        ..DiffRow::new(change, b, depth)
+                    }
+                }
+                Change::Added => {
+                    let (b, depth) = after.next().unwrap();
+                    DiffRow {
+                        after: Some(b.durations().1),
+                        ..DiffRow::new(change, b, depth)
+                    }
+                }
+                Change::Missing => {
+                    let (a, depth) = before.next().unwrap();
+                    DiffRow {
+                        before: Some(a.durations().1),
+                        ..DiffRow::new(change, a, depth)
+                    }
+                }
+            };
+            rows.push(row);
+        }
+
+        SessionDiff {
+            before: self.id,
+            after: other.id,
+            total_before: self.total_duration(),
+            total_after: other.total_duration(),
+            rows,
+        }
+    }
+}
+
+/// The most cells of the table used to line up the events that differ, around 16MB, before we give up on lining
+/// them up; see [Session::diff].
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Formats a change in duration with its sign, i.e. `+12` or `-3`.
+fn signed_duration(config: &Cli, micros: i64) -> String {
+    let sign = if micros < 0 { '-' } else { '+' };
+    format!("{sign}{}", format_duration(config, micros.abs()))
+}
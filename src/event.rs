@@ -1,7 +1,13 @@
-use crate::{cli::DurationFormat, records::EventRecord, Cli, COMPLAIN_ABOUT_TRACE_SIZE};
+use crate::{
+    cli::DurationFormat,
+    color::{self, DIM, SLOW},
+    records::EventRecord,
+    redact::{is_word_char, split_ip, split_while},
+    Cli, COMPLAIN_ABOUT_TRACE_SIZE,
+};
 use chrono::Duration;
-use serde::Deserialize;
-use std::{fmt::Display, net::IpAddr};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt::Display, net::IpAddr};
 use uuid::Uuid;
 
 /// All of the information related to an event, as well as all child events.
@@ -38,6 +44,43 @@ impl Event {
         self.activity.len()
     }
 
+    /// The activity with anything that varies between otherwise identical events masked out, so that similar events
+    /// can be grouped together.
+    ///
+    /// IP addresses become `<ip>` and numbers, including tokens, become `#`, so that
+    /// `read_data: sending a message to /172.17.0.3` becomes `read_data: sending a message to /<ip>`, and
+    /// `Creating shard reader on shard: 15` becomes `Creating shard reader on shard: #`.
+    pub fn normalised_activity(&self) -> String {
+        let mut output = String::with_capacity(self.activity.len());
+        let mut rest = self.activity.as_str();
+
+        while let Some(c) = rest.chars().next() {
+            if !output.ends_with(is_word_char) {
+                if let Some((_, remaining)) = split_ip(rest) {
+                    output.push_str("<ip>");
+                    rest = remaining;
+                    continue;
+                }
+
+                let (number, remaining) = split_while(rest, |c| c.is_ascii_digit());
+                if !number.is_empty() && !remaining.starts_with(is_word_char) {
+                    // Tokens are signed
+                    if output.ends_with('-') {
+                        output.pop();
+                    }
+                    output.push('#');
+                    rest = remaining;
+                    continue;
+                }
+            }
+
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+
+        output
+    }
+
     /// Return the total duration of this span and it's children, and the duration of just this span.
     ///
     /// First field is the total, second field is just this span.
@@ -69,35 +112,7 @@ impl Event {
     /// `session_duration` is the total duration of the session.
     pub fn waterfall(&self, config: &Cli, offset: i64, session_duration: i64) -> String {
         let (total_dur, self_dur) = self.durations();
-        let e_start = offset;
-        let e_end = offset + self_dur;
-        let e_tail = offset + total_dur;
-
-        // Calculate positions as a factor of the waterfall width
-        let e_start_pos = (e_start as f64 * *config.waterfall_width as f64
-            / session_duration as f64)
-            .floor() as usize;
-        let e_end_pos = ((e_end as f64 * *config.waterfall_width as f64 / session_duration as f64)
-            .floor() as usize)
-            .max(e_start_pos + 1);
-        let e_tail_pos =
-            ((e_tail as f64 * *config.waterfall_width as f64 / session_duration as f64).floor()
-                as usize)
-                .max(e_start_pos + 1);
-
-        let block_width = e_end_pos - e_start_pos;
-        let tail_width = e_tail_pos - e_end_pos;
-        let rem_width = *config.waterfall_width - e_start_pos - block_width - tail_width;
-
-        let tail = match tail_width {
-            0 => "",
-            _ => "┤",
-        };
-
-        format!(
-            "[{:<e_start_pos$}{:█<block_width$}{tail:─>tail_width$}{:<rem_width$}]",
-            "", "", ""
-        )
+        waterfall_str(config, offset, self_dur, total_dur, session_duration)
     }
 
     /// Generates a texttual representation of the event to display alongside the waterfall view.
@@ -109,6 +124,8 @@ impl Event {
     ///     0 10.17.145.76    Querying is done
     /// ```
     ///
+    /// Slow events have their duration highlighted, if a threshold and colour are configured.
+    ///
     /// This can be extended with the [config][Cli] to include the [event id][Event::id],
     /// the [local][Event::span_id] and [parent][Event::parent_span_id] span IDs, and the [thread name][Event::thread]:
     ///
@@ -123,21 +140,8 @@ impl Event {
         depth: usize,
         max_depth: usize,
     ) -> String {
-        let duration = match config.duration_format {
-            DurationFormat::Millis => self.duration.num_milliseconds(),
-            DurationFormat::Micros => self
-                .duration
-                .num_microseconds()
-                .expect(COMPLAIN_ABOUT_TRACE_SIZE),
-        }
-        .to_string();
-
-        // Activity tree
-        let mut tree_bit = format!("{:│>t_depth$}", "├", t_depth = depth + 1);
-        if self.is_parent() {
-            tree_bit.push('┬');
-        }
-        let tree = format!("{tree_bit:─<t_depth$}", t_depth = max_depth + 2);
+        let duration = duration_str(config, self.durations().1);
+        let tree = tree_str(config, depth, max_depth, self.is_parent());
 
         event_display_str(
             config,
@@ -157,6 +161,10 @@ impl Event {
         !self.child_events.is_empty()
     }
 
+    pub(crate) fn children(&self) -> &[Event] {
+        &self.child_events
+    }
+
     pub(crate) fn count_including_children(&self) -> usize {
         1 + self
             .child_events
@@ -172,6 +180,23 @@ impl Event {
         }
     }
 
+    /// Adds this event to the critical path, followed by the critical path through its children; see
+    /// [Session::critical_path][crate::Session::critical_path].
+    pub(crate) fn recurse_critical_path<'a>(&'a self, path: &mut Vec<&'a Event>) {
+        path.push(self);
+        for child in slowest_span(&self.child_events) {
+            child.recurse_critical_path(path);
+        }
+    }
+
+    pub(crate) fn recurse_events_mut(&mut self, f: &mut dyn FnMut(&mut Event)) {
+        f(self);
+        for child in &mut self.child_events {
+            child.recurse_events_mut(f);
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
     pub(crate) fn try_add_child(&mut self, child_event: Event) -> Result<(), Event> {
         // Base case, the provided event is a direct child of this event
         if child_event.parent_span_id == self.span_id {
@@ -216,6 +241,116 @@ impl From<EventRecord> for Event {
     }
 }
 
+/// Generates the waterfall chart used by [Event::waterfall], for a span of `self_dur` microseconds starting at `offset`
+/// with children running until `offset + total_dur`.
+///
+/// Here to allow us to draw the same chart for groups of events.
+pub fn waterfall_str(
+    config: &Cli,
+    offset: i64,
+    self_dur: i64,
+    total_dur: i64,
+    session_duration: i64,
+) -> String {
+    let e_start = offset;
+    let e_end = offset + self_dur;
+    let e_tail = offset + total_dur;
+
+    // Calculate positions as a factor of the waterfall width
+    let e_start_pos = (e_start as f64 * *config.waterfall_width as f64 / session_duration as f64)
+        .floor() as usize;
+    let e_end_pos = ((e_end as f64 * *config.waterfall_width as f64 / session_duration as f64)
+        .floor() as usize)
+        .max(e_start_pos + 1);
+    let e_tail_pos = ((e_tail as f64 * *config.waterfall_width as f64 / session_duration as f64)
+        .floor() as usize)
+        .max(e_start_pos + 1);
+
+    let block_width = e_end_pos - e_start_pos;
+    let tail_width = e_tail_pos - e_end_pos;
+    let rem_width = *config.waterfall_width - e_start_pos - block_width - tail_width;
+
+    let tail = match tail_width {
+        0 => "",
+        _ => "┤",
+    };
+
+    format!(
+        "[{:<e_start_pos$}{:█<block_width$}{tail:─>tail_width$}{:<rem_width$}]",
+        "", "", ""
+    )
+}
+
+/// The events belonging to whichever of their spans took the longest, in their original order.
+///
+/// Sibling spans run alongside each other, such as a mutation being applied by each replica, so only the slowest held
+/// up the span that started them.
+pub(crate) fn slowest_span(events: &[Event]) -> Vec<&Event> {
+    // In the order that they're first seen, so that the first of any equally slow spans wins
+    let mut spans: Vec<(SpanId, i64)> = Vec::new();
+    for event in events {
+        match spans
+            .iter_mut()
+            .find(|(span_id, _)| *span_id == event.span_id)
+        {
+            Some((_, total)) => *total += event.durations().0,
+            None => spans.push((event.span_id, event.durations().0)),
+        }
+    }
+
+    let slowest = spans
+        .iter()
+        .fold(
+            None,
+            |slowest: Option<&(SpanId, i64)>, span| match slowest {
+                Some(slowest) if slowest.1 >= span.1 => Some(slowest),
+                _ => Some(span),
+            },
+        )
+        .map(|(span_id, _)| *span_id);
+
+    events
+        .iter()
+        .filter(|event| Some(event.span_id) == slowest)
+        .collect()
+}
+
+/// Formats a duration in microseconds for the `dur` column, highlighting it if it is slow.
+pub(crate) fn duration_str(config: &Cli, micros: i64) -> String {
+    highlight_slow(config, &format_duration(config, micros), micros)
+}
+
+/// Formats a duration in microseconds in the configured unit.
+pub(crate) fn format_duration(config: &Cli, micros: i64) -> String {
+    match config.duration_format {
+        DurationFormat::Millis => micros / 1000,
+        DurationFormat::Micros => micros,
+    }
+    .to_string()
+}
+
+/// Pads the formatted duration for the `dur` column, and highlights it if `micros` is above the slow threshold.
+pub(crate) fn highlight_slow(config: &Cli, duration: &str, micros: i64) -> String {
+    // Padded before painting, as the escape codes would otherwise count towards the width
+    let duration = format!("{duration:d_min$}", d_min = *config.min_duration_width);
+    match config.slow_threshold {
+        Some(threshold) if micros >= threshold => {
+            color::paint(&duration, SLOW, config.color.enabled())
+        }
+        _ => duration,
+    }
+}
+
+/// Draws the branches of the activity tree leading to an event at the given depth.
+pub(crate) fn tree_str(config: &Cli, depth: usize, max_depth: usize, is_parent: bool) -> String {
+    let mut tree_bit = format!("{:│>t_depth$}", "├", t_depth = depth + 1);
+    if is_parent {
+        tree_bit.push('┬');
+    }
+    let tree = format!("{tree_bit:─<t_depth$}", t_depth = max_depth + 2);
+    color::paint(&tree, DIM, config.color.enabled())
+}
+
 /// Generates the formatted string used by [Event::display].
 ///
 /// Here to allow us to re-use the same formatting options for the headers.
@@ -236,7 +371,14 @@ pub fn event_display_str(
     let a_min = min_activity_width.min(*config.max_activity_width);
     let a_max = *config.max_activity_width;
 
-    let mut output = format!("{duration:d_min$} {source:15} {tree} {activity:a_min$.a_max$}");
+    // Only a chart fitted to the terminal marks where activities have been cut short
+    let mut output = match config.auto_width {
+        true => format!(
+            "{duration:d_min$} {source:15} {tree} {:a_min$}",
+            truncate(activity, a_max)
+        ),
+        false => format!("{duration:d_min$} {source:15} {tree} {activity:a_min$.a_max$}"),
+    };
 
     if config.show_event_id {
         output.push_str(&format!(" {event_id:37}"));
@@ -251,8 +393,21 @@ pub fn event_display_str(
     output
 }
 
+/// Shortens the text to at most `max_width` characters, marking that it has been cut short with an ellipsis.
+fn truncate(text: &str, max_width: usize) -> Cow<'_, str> {
+    if text.chars().count() <= max_width {
+        return Cow::Borrowed(text);
+    }
+
+    let mut truncated: String = text.chars().take(max_width.saturating_sub(1)).collect();
+    if max_width > 0 {
+        truncated.push('…');
+    }
+    Cow::Owned(truncated)
+}
+
 /// Wrapper type for the `i64` used by Scylla for span IDs.
-#[derive(Debug, PartialEq, Clone, Copy, Default, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, Deserialize, Serialize)]
 pub struct SpanId(i64);
 
 impl SpanId {
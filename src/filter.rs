@@ -0,0 +1,133 @@
+use crate::{Cli, Event, Session, SessionFilterOptions, COMPLAIN_ABOUT_TRACE_SIZE};
+use regex::Regex;
+use std::net::IpAddr;
+
+/// Which events of a [Session][crate::Session] to show, as configured by the [Cli].
+///
+/// An event is shown if it matches every one of the configured filters, or if it is an ancestor of such an event, so
+/// that the matching events are still shown in context.
+#[derive(Debug, Default, Clone)]
+pub struct EventFilter {
+    /// Only events from one of these nodes; any node if empty
+    pub nodes: Vec<IpAddr>,
+    /// Only events from one of these threads, i.e. `shard 3`; any thread if empty
+    pub threads: Vec<String>,
+    /// Only events with an activity matching this pattern
+    pub activity: Option<Regex>,
+    /// Only events that took at least this many microseconds, not including their children
+    pub min_duration: Option<i64>,
+    /// Only events at most this deep in the tree, where the root events are at depth 0
+    pub max_depth: Option<usize>,
+}
+
+impl EventFilter {
+    /// Whether the filter lets every event through.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+            && self.threads.is_empty()
+            && self.activity.is_none()
+            && self.min_duration.is_none()
+            && self.max_depth.is_none()
+    }
+
+    /// Whether the event, at the given depth, matches every one of the filters.
+    pub fn matches(&self, event: &Event, depth: usize) -> bool {
+        (self.nodes.is_empty() || self.nodes.contains(&event.source))
+            && (self.threads.is_empty() || self.threads.contains(&event.thread))
+            && self
+                .activity
+                .as_ref()
+                .is_none_or(|activity| activity.is_match(&event.activity))
+            && self
+                .min_duration
+                .is_none_or(|min_duration| event.durations().1 >= min_duration)
+            && self.max_depth.is_none_or(|max_depth| depth <= max_depth)
+    }
+}
+
+impl From<&Cli> for EventFilter {
+    fn from(cli: &Cli) -> Self {
+        Self {
+            nodes: cli.node.clone(),
+            threads: cli.thread.clone(),
+            activity: cli.activity.clone(),
+            min_duration: cli.min_duration,
+            max_depth: cli.max_depth,
+        }
+    }
+}
+
+/// Which sessions to pick out of many, as configured by the [SessionFilterOptions].
+///
+/// A session is picked if it matches every one of the configured filters.
+#[derive(Debug, Default, Clone)]
+pub struct SessionFilter {
+    /// Only sessions coordinated by one of these nodes; any node if empty
+    pub coordinators: Vec<IpAddr>,
+    /// Only sessions from one of these clients; any client if empty
+    pub clients: Vec<IpAddr>,
+    pub command: Option<String>,
+    pub username: Option<String>,
+    /// Only sessions whose query names this keyspace, ignoring case
+    pub keyspace: Option<String>,
+    /// Only sessions whose query names this table, ignoring case
+    pub table: Option<String>,
+    /// Only sessions that took at least this many microseconds
+    pub min_duration: Option<i64>,
+    /// Only sessions with a [normalised query][Session::normalised_query] matching this pattern
+    pub query: Option<Regex>,
+}
+
+impl SessionFilter {
+    /// Whether the session matches every one of the filters.
+    pub fn matches(&self, session: &Session) -> bool {
+        let (keyspace, table) = match self.keyspace.is_some() || self.table.is_some() {
+            true => session.keyspace_and_table(),
+            false => (None, None),
+        };
+        let same_name = |wanted: &Option<String>, name: Option<String>| {
+            wanted
+                .as_ref()
+                .is_none_or(|wanted| name.is_some_and(|name| name.eq_ignore_ascii_case(wanted)))
+        };
+
+        (self.coordinators.is_empty() || self.coordinators.contains(&session.coordinator))
+            && (self.clients.is_empty() || self.clients.contains(&session.client))
+            && self
+                .command
+                .as_ref()
+                .is_none_or(|command| session.command.eq_ignore_ascii_case(command))
+            && self
+                .username
+                .as_ref()
+                .is_none_or(|username| session.username.as_ref() == Some(username))
+            && same_name(&self.keyspace, keyspace)
+            && same_name(&self.table, table)
+            && self.min_duration.is_none_or(|min_duration| {
+                session
+                    .duration
+                    .num_microseconds()
+                    .expect(COMPLAIN_ABOUT_TRACE_SIZE)
+                    >= min_duration
+            })
+            && self
+                .query
+                .as_ref()
+                .is_none_or(|query| query.is_match(&session.normalised_query()))
+    }
+}
+
+impl From<&SessionFilterOptions> for SessionFilter {
+    fn from(options: &SessionFilterOptions) -> Self {
+        Self {
+            coordinators: options.coordinator.clone(),
+            clients: options.client.clone(),
+            command: options.command.clone(),
+            username: options.username.clone(),
+            keyspace: options.keyspace.clone(),
+            table: options.table.clone(),
+            min_duration: options.min_session_duration,
+            query: options.query.clone(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{
+    event::{event_display_str, format_duration, highlight_slow, tree_str, waterfall_str},
+    Cli, Event,
+};
+
+/// A run of consecutive, similar events that are shown as a single row; see [Session::group_events][crate::Session::group_events].
+#[derive(Debug)]
+pub struct EventGroup<'a> {
+    /// Position of the first event of the group within [Session::events][crate::Session::events]
+    pub index: usize,
+    pub events: Vec<&'a Event>,
+    pub depth: usize,
+}
+
+impl<'a> EventGroup<'a> {
+    /// The [normalised activity][Event::normalised_activity] shared by every event in the group.
+    pub fn activity(&self) -> String {
+        self.events[0].normalised_activity()
+    }
+
+    /// The sum of the durations of the events in the group.
+    ///
+    /// Grouped events never have children, so this is also the time from the start of the first to the end of the last.
+    pub fn total_duration(&self) -> i64 {
+        self.events.iter().map(|e| e.durations().1).sum()
+    }
+
+    /// The duration of the slowest event in the group.
+    pub fn max_duration(&self) -> i64 {
+        self.events
+            .iter()
+            .map(|e| e.durations().1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The length of the activity as shown by [EventGroup::display], to line up the columns that follow it.
+    pub fn activity_length(&self, config: &Cli) -> usize {
+        self.summary(config).chars().count()
+    }
+
+    /// Generate a waterfall chart covering every event in the group; see [Event::waterfall].
+    pub fn waterfall(&self, config: &Cli, offset: i64, session_duration: i64) -> String {
+        let total = self.total_duration();
+        waterfall_str(config, offset, total, total, session_duration)
+    }
+
+    /// Generates a textual representation of the group to display alongside the waterfall view; see [Event::display].
+    ///
+    /// The duration is the total of the group, and the activity is followed by the number of events and the duration
+    /// of the slowest:
+    ///
+    /// ```text
+    /// 1204   172.17.0.3      │├─ Creating shard reader on shard: # (×16, max 140)
+    /// ```
+    ///
+    /// The ids and span ids are those of the first event of the group, and the thread is only shown if it is the same
+    /// for every event.
+    pub fn display(&self, config: &Cli, min_activity_width: usize, max_depth: usize) -> String {
+        let first = self.events[0];
+        let thread = match self.events.iter().all(|e| e.thread == first.thread) {
+            true => first.thread.as_str(),
+            false => "",
+        };
+
+        event_display_str(
+            config,
+            min_activity_width,
+            // Only slow if one of the events was, rather than all of them together
+            &highlight_slow(
+                config,
+                &format_duration(config, self.total_duration()),
+                self.max_duration(),
+            ),
+            &first.source.to_string(),
+            &tree_str(config, self.depth, max_depth, false),
+            &self.summary(config),
+            &first.id.to_string(),
+            &first.span_id.to_string(),
+            &first.parent_span_id.to_string(),
+            thread,
+        )
+    }
+
+    /// The normalised activity followed by the number of events and the duration of the slowest.
+    fn summary(&self, config: &Cli) -> String {
+        format!(
+            "{} (×{}, max {})",
+            self.activity(),
+            self.events.len(),
+            format_duration(config, self.max_duration())
+        )
+    }
+}
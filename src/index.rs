@@ -0,0 +1,171 @@
+use crate::{CsvFormat, CsvParsingError};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+/// The extension appended to the path of the events CSV to give the path of its index.
+const INDEX_EXTENSION: &str = "idx";
+
+/// A side index of where the rows of each session lie within an exported `events.csv`.
+///
+/// Scanning a multi-gigabyte export of `system_traces.events` for a single session is slow, so on first use we scan
+/// the whole file once and record the byte ranges of each session's rows in a small CSV next to it, i.e.
+/// `events.csv.idx`. Later lookups seek straight to those ranges.
+///
+/// The index is rebuilt whenever the events CSV is newer than it.
+#[derive(Debug, Default)]
+pub struct EventsIndex {
+    ranges: HashMap<Uuid, Vec<IndexEntry>>,
+}
+
+/// A contiguous run of rows for a single session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub session_id: Uuid,
+    /// Byte offset of the first row of the run
+    pub start: u64,
+    /// Byte offset just past the last row of the run
+    pub end: u64,
+    /// Line number of the first row of the run, kept so that errors can point at the right line
+    pub line: u64,
+    /// Record number of the first row of the run
+    pub record: u64,
+}
+
+impl IndexEntry {
+    /// The position to seek a [csv::Reader] to in order to read the first row of the run.
+    pub fn position(&self) -> csv::Position {
+        let mut position = csv::Position::new();
+        position
+            .set_byte(self.start)
+            .set_line(self.line)
+            .set_record(self.record);
+        position
+    }
+}
+
+impl EventsIndex {
+    /// Loads the index for the given events CSV, building and saving it first if it is missing or stale.
+    ///
+    /// Failing to save the index is not an error; it is still used for this lookup, and will be built again next
+    /// time.
+    pub fn load_or_build(events: &Path, format: &CsvFormat) -> Result<Self, CsvParsingError> {
+        let index_path = index_path(events);
+        if is_fresh(events, &index_path) {
+            if let Ok(index) = Self::load(&index_path) {
+                return Ok(index);
+            }
+        }
+
+        let index = Self::build(File::open(events)?, format)?;
+        let _ = index.save(&index_path);
+        Ok(index)
+    }
+
+    /// Scans the whole events CSV, recording the byte ranges of each session's rows.
+    ///
+    /// Rows with a session id that can't be parsed are left out of the index entirely. A CSV without a session_id
+    /// column can't be indexed at all.
+    pub fn build(reader: impl Read, format: &CsvFormat) -> Result<Self, CsvParsingError> {
+        let mut reader = format.reader(reader);
+        let headers = format
+            .headers(&mut reader)
+            .map_err(CsvParsingError::Headers)?;
+        let column = format
+            .session_id_column(headers.as_ref())
+            .ok_or(CsvParsingError::MissingColumn)?;
+
+        let mut index = Self::default();
+        let mut current: Option<IndexEntry> = None;
+        let mut record = csv::ByteRecord::new();
+
+        loop {
+            let position = reader.position().clone();
+            let more = match reader.read_byte_record(&mut record) {
+                Ok(more) => more,
+                Err(err) if err.is_io_error() => return Err(CsvParsingError::Index(err)),
+                // Any other errors are left for whoever reads the rows to report.
+                Err(_) => true,
+            };
+
+            let session_id = record
+                .get(column)
+                .and_then(|id| Uuid::try_parse_ascii(id).ok())
+                .filter(|_| more);
+
+            if let Some(mut entry) = current.take() {
+                if Some(entry.session_id) == session_id {
+                    current = Some(entry);
+                    continue;
+                }
+
+                entry.end = position.byte();
+                index.insert(entry);
+            }
+
+            if !more {
+                break;
+            }
+
+            current = session_id.map(|session_id| IndexEntry {
+                session_id,
+                start: position.byte(),
+                end: position.byte(),
+                line: position.line(),
+                record: position.record(),
+            });
+        }
+
+        Ok(index)
+    }
+
+    /// The runs of rows belonging to the given session, in the order they appear in the file.
+    pub fn get(&self, session_id: &Uuid) -> &[IndexEntry] {
+        self.ranges
+            .get(session_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn insert(&mut self, entry: IndexEntry) {
+        self.ranges.entry(entry.session_id).or_default().push(entry);
+    }
+
+    fn load(path: &Path) -> Result<Self, csv::Error> {
+        let mut index = Self::default();
+        for entry in csv::Reader::from_path(path)?.deserialize() {
+            index.insert(entry?);
+        }
+        Ok(index)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), csv::Error> {
+        let mut writer = csv::Writer::from_path(path)?;
+        for entry in self.ranges.values().flatten() {
+            writer.serialize(entry)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn index_path(events: &Path) -> PathBuf {
+    let mut path = events.as_os_str().to_owned();
+    path.push(".");
+    path.push(INDEX_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// The index is fresh if it was written after the events CSV was last modified.
+fn is_fresh(events: &Path, index: &Path) -> bool {
+    let modified = |path: &Path| path.metadata().and_then(|metadata| metadata.modified());
+    match (modified(events), modified(index)) {
+        (Ok(events), Ok(index)) => index >= events,
+        _ => false,
+    }
+}
@@ -0,0 +1,116 @@
+use flate2::{bufread::MultiGzDecoder, write::GzEncoder, Compression};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Stdout, Write},
+    path::Path,
+};
+
+/// The first bytes of a gzip stream.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+/// The first bytes of a zstd frame.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Whether the given path refers to stdin (or stdout) rather than a file.
+pub(crate) fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Opens the file at the given path for reading, or stdin if the path is `-`.
+///
+/// The contents are transparently decompressed if they are gzip or zstd compressed.
+pub(crate) fn open(path: &Path) -> std::io::Result<Box<dyn BufRead>> {
+    if is_stdin(path) {
+        decompress(std::io::stdin())
+    } else {
+        decompress(File::open(path)?)
+    }
+}
+
+/// Whether the file at the given path is gzip or zstd compressed.
+pub(crate) fn is_compressed(path: &Path) -> std::io::Result<bool> {
+    let mut magic = Vec::with_capacity(ZSTD_MAGIC.len());
+    File::open(path)?
+        .take(ZSTD_MAGIC.len() as u64)
+        .read_to_end(&mut magic)?;
+
+    Ok(magic.starts_with(GZIP_MAGIC) || magic.starts_with(ZSTD_MAGIC))
+}
+
+/// Wraps the reader in a decoder if the stream starts with the magic bytes of gzip or zstd.
+///
+/// We sniff the contents rather than relying on the file extension so that this also works for stdin.
+pub(crate) fn decompress<'a>(reader: impl Read + 'a) -> std::io::Result<Box<dyn BufRead + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let magic = reader.fill_buf()?;
+
+    if magic.starts_with(GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else if magic.starts_with(ZSTD_MAGIC) {
+        Ok(Box::new(BufReader::new(zstd::Decoder::with_buffer(
+            reader,
+        )?)))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Creates the file at the given path for writing, or writes to stdout if the path is `-`.
+///
+/// The contents are compressed if the path has a `.gz` or `.zst` extension.
+pub(crate) fn create(path: &Path) -> std::io::Result<Output> {
+    if is_stdin(path) {
+        return Ok(Output::Stdout(std::io::stdout()));
+    }
+
+    let file = BufWriter::new(File::create(path)?);
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("gz") => Ok(Output::Gzip(GzEncoder::new(file, Compression::default()))),
+        Some("zst") => Ok(Output::Zstd(zstd::Encoder::new(file, 0)?)),
+        _ => Ok(Output::File(file)),
+    }
+}
+
+/// Somewhere to write to, as opened by [create].
+///
+/// The encoders are kept as they are, rather than behind a `dyn Write`, so that they can be finished explicitly; see
+/// [Output::finish].
+pub(crate) enum Output {
+    Stdout(Stdout),
+    File(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Output {
+    /// Writes out anything still buffered, along with the trailer of a compressed stream.
+    ///
+    /// Dropping an encoder also writes its trailer, but any error doing so is lost.
+    pub(crate) fn finish(self) -> std::io::Result<()> {
+        match self {
+            Self::Stdout(mut stdout) => stdout.flush(),
+            Self::File(mut file) => file.flush(),
+            Self::Gzip(encoder) => encoder.finish()?.flush(),
+            Self::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::File(file) => file.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::File(file) => file.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
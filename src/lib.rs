@@ -1,17 +1,42 @@
 //! A tool for visualising the traces emitted by ScyllaDB.
 
+mod archive;
+mod breakdown;
 mod cli;
+mod color;
+mod cqlsh;
 mod csv;
 mod data_source;
 mod db;
+mod diff;
 mod event;
+mod filter;
+mod group;
+mod index;
+mod input;
+mod lint;
+mod paxos;
+mod profile;
+mod reads;
 mod records;
+mod redact;
+mod replicas;
+mod report;
 mod session;
+mod slow_log;
+mod stats;
+mod top;
+mod tui;
 
-use std::{net::SocketAddr, path::PathBuf};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-pub use {crate::csv::*, cli::*, db::*, event::*, records::*, session::*};
+pub use {
+    crate::csv::*, archive::*, breakdown::*, cli::*, cqlsh::*, db::*, diff::*, event::*, filter::*,
+    group::*, index::*, lint::*, paxos::*, profile::*, reads::*, records::*, redact::*,
+    replicas::*, report::*, session::*, slow_log::*, stats::*, top::*, tui::*,
+};
 
 /// It's possible for a [chrono::Duration] to have more than 2^63 microseconds and overflow, we ignore that possibility.
 pub const COMPLAIN_ABOUT_TRACE_SIZE: &str =
@@ -33,15 +58,194 @@ pub fn session_from_csv(
     Ok(Session::new(session_record, event_records))
 }
 
-/// Constructs a [Session] instance from a live database, given the [DbModeOptions] config.
+/// Constructs a [Session] instance using all of the options given in the [CsvModeOptions] config.
+///
+/// In lenient mode, any rows that had to be skipped are returned alongside the session, so they can be reported.
+///
+/// See [session_from_csv].
+pub fn session_from_csv_options(
+    options: &CsvModeOptions,
+) -> Result<(Session, Vec<RowError>), Box<dyn std::error::Error>> {
+    let session_id = Uuid::try_parse(&options.session_id)?;
+    let source = match options.bundle {
+        Some(ref bundle) => CsvSource::from_bundle(bundle, session_id),
+        None => CsvSource::new(&options.sessions_path, &options.events_path, session_id),
+    };
+    let source = source
+        .with_format(CsvFormat::from(&options.format))
+        .with_index(options.index)
+        .with_full_scan(options.full_scan);
+
+    let (session_record, event_records, skipped) = if options.lenient {
+        source.get_data_lenient()?
+    } else {
+        let (session_record, event_records) = source.get_data()?;
+        (session_record, event_records, Vec::new())
+    };
+
+    Ok((Session::new(session_record, event_records), skipped))
+}
+
+/// Constructs a [Session] instance from a live database, over the connection of the given [DbSource].
 ///
 /// This [Session] instance contains all of the information available from the `system_traces.sessions` table, as well
 /// as all of the information for the [events][Event] relating to that session from the `system_traces.events` table.
 pub async fn session_from_db(
-    addr: SocketAddr,
+    source: &DbSource,
     session_id: &str,
 ) -> Result<Session, Box<dyn std::error::Error>> {
     let session_id = Uuid::try_parse(session_id)?;
-    let (session_record, event_records) = DbSource::new(addr, session_id).get_data().await?;
+    let (session_record, event_records) = source.get_data(session_id).await?;
     Ok(Session::new(session_record, event_records))
 }
+
+/// Constructs a [Session] instance from the tracing output of cqlsh, given the [CqlshModeOptions] config.
+///
+/// This [Session] instance contains what little session information cqlsh prints, as well as all of the
+/// [events][Event] listed in the tracing table.
+pub fn session_from_cqlsh(path: &PathBuf) -> Result<Session, Box<dyn std::error::Error>> {
+    let (session_record, event_records) = CqlshSource::new(path).get_data()?;
+    Ok(Session::new(session_record, event_records))
+}
+
+/// Constructs a [Session] instance from a seella archive, given the [ArchiveModeOptions] config.
+///
+/// The session id may be left out if the archive only contains a single session.
+pub fn session_from_archive(
+    path: &PathBuf,
+    session_id: Option<&str>,
+) -> Result<Session, Box<dyn std::error::Error>> {
+    let session_id = session_id.map(Uuid::try_parse).transpose()?;
+    let (session_record, event_records) = ArchiveSource::new(path, session_id).get_data()?;
+    Ok(Session::new(session_record, event_records))
+}
+
+/// Constructs a [Session] instance for every session in the CSVs given in the [CsvSessionsOptions] config.
+///
+/// In lenient mode, any rows that had to be skipped are returned alongside the sessions, so they can be reported.
+pub fn sessions_from_csv_options(
+    options: &CsvSessionsOptions,
+) -> Result<(Vec<Session>, Vec<RowError>), Box<dyn std::error::Error>> {
+    let input = match options.bundle {
+        Some(ref bundle) => CsvInput::Bundle(bundle),
+        None => CsvInput::Pair {
+            sessions: &options.sessions_path,
+            events: &options.events_path,
+        },
+    };
+    let source = CsvSource::every_session(input).with_format(CsvFormat::from(&options.format));
+
+    let (records, skipped) = if options.lenient {
+        source.get_all_data_lenient()?
+    } else {
+        (source.get_all_data()?, Vec::new())
+    };
+
+    let sessions = records
+        .into_iter()
+        .map(|(session_record, event_records)| Session::new(session_record, event_records))
+        .collect();
+
+    Ok((sessions, skipped))
+}
+
+/// Constructs a [Session] instance for every session started within a window of time, from a live database.
+pub async fn sessions_from_db(
+    source: &DbSource,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<Vec<Session>, Box<dyn std::error::Error>> {
+    let records = DbWindowSource::new(source.clone(), since, until)
+        .get_all_data()
+        .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|(session_record, event_records)| Session::new(session_record, event_records))
+        .collect())
+}
+
+/// Constructs a [Session] instance for every session written to a live database since the last poll of the
+/// [DbFollowSource].
+pub async fn sessions_from_db_poll(
+    source: &mut DbFollowSource,
+) -> Result<Vec<Session>, Box<dyn std::error::Error>> {
+    Ok(source
+        .poll()
+        .await?
+        .into_iter()
+        .map(|(session_record, event_records)| Session::new(session_record, event_records))
+        .collect())
+}
+
+/// Constructs a [Session] instance for every session in a seella archive.
+pub fn sessions_from_archive(path: &PathBuf) -> Result<Vec<Session>, Box<dyn std::error::Error>> {
+    let mut sessions = Vec::new();
+    for archived in ArchiveSource::new(path, None).sessions()? {
+        let archived = archived?;
+        sessions.push(Session::new(archived.session, archived.events));
+    }
+    Ok(sessions)
+}
+
+/// Reads the entries of the slow query log from the CSV given in the [CsvSlowLogOptions] config, oldest first.
+pub fn slow_log_from_csv_options(
+    options: &CsvSlowLogOptions,
+) -> Result<Vec<SlowLogRecord>, Box<dyn std::error::Error>> {
+    let input = CsvInput::Pair {
+        sessions: &options.sessions_path,
+        events: &options.events_path,
+    };
+    let mut entries = CsvSource::every_session(input)
+        .with_format(CsvFormat::from(&options.format))
+        .get_slow_log(&options.slow_log_path)?;
+    entries.sort_by_key(|entry| entry.date);
+
+    Ok(entries)
+}
+
+/// Reads the entries of the slow query log for queries started within a window of time, from a live database.
+pub async fn slow_log_from_db(
+    source: &DbSource,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<Vec<SlowLogRecord>, Box<dyn std::error::Error>> {
+    Ok(DbSlowLogSource::new(source.clone(), since, until)
+        .get_entries()
+        .await?)
+}
+
+/// Exports the given sessions from a live database into a seella archive, given the [ExportOptions] config.
+///
+/// If `redact` is set, the sessions are passed through a single [Redactor] before they are written, so that each node
+/// keeps the same anonymised address across the whole archive.
+///
+/// The sessions are all read a few at a time, before any are written.
+///
+/// Returns the number of sessions written.
+pub async fn export_from_db(
+    source: &DbSource,
+    session_ids: &[String],
+    output: &Path,
+    redact: bool,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let session_ids = session_ids
+        .iter()
+        .map(|session_id| Uuid::try_parse(session_id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let sessions = source.get_many(session_ids).await?;
+
+    let mut redactor = Redactor::new();
+    let mut writer = ArchiveWriter::create(output)?;
+    let count = sessions.len();
+    for (mut session_record, mut event_records) in sessions {
+        if redact {
+            redactor.redact_records(&mut session_record, &mut event_records);
+        }
+        writer.write(session_record, event_records)?;
+    }
+    writer.finish()?;
+
+    Ok(count)
+}
@@ -0,0 +1,157 @@
+use crate::{
+    color::{self, BOLD, SLOW},
+    event::format_duration,
+    replicas::parse_ips,
+    Cli, Session, SpanId, COMPLAIN_ABOUT_TRACE_SIZE,
+};
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Display, net::IpAddr};
+
+/// The kinds of things that [Session::lint] looks out for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// A long wait between two events of the same span, i.e. at least `--max-gap`
+    Gap,
+    /// Time going backwards between two events of the same span, which points at clock trouble or missing rows
+    ElapsedBackwards,
+    /// A request being held back because the node is overloaded
+    Throttling,
+    /// A request being sent to another replica because the first was slow to respond
+    SpeculativeRetry,
+    Timeout,
+    /// Replicas that a write should have gone to, but were down
+    DeadReplica,
+    /// A write forwarded to a replica in another datacenter
+    CrossDc,
+}
+
+impl Display for LintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LintKind::Gap => "gap",
+            LintKind::ElapsedBackwards => "elapsed backwards",
+            LintKind::Throttling => "throttling",
+            LintKind::SpeculativeRetry => "speculative retry",
+            LintKind::Timeout => "timeout",
+            LintKind::DeadReplica => "dead replica",
+            LintKind::CrossDc => "cross dc",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Something unusual about a single event.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Finding {
+    /// The number of the event's row, as printed by [Session::display]; with `--group`, an event within a group is
+    /// given the group's row
+    pub row: usize,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+impl Session {
+    /// Looks for anything unusual about the trace, in the order of the events.
+    ///
+    /// Gaps are measured using the `source_elapsed` of the events, which counts up from the start of each span on each
+    /// node; see [LintKind] for everything else that is flagged.
+    pub fn lint(&self, config: &Cli) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        // The elapsed time of the last event of each span on each node
+        let mut last_elapsed: HashMap<(IpAddr, SpanId), i64> = HashMap::new();
+        let events = self.events();
+        // A group is shown on the row of its first event
+        let mut rows: Vec<usize> = (1..=events.len()).collect();
+        for group in self.collapsed_groups(config) {
+            rows[group.index..group.index + group.events.len()].fill(group.index + 1);
+        }
+
+        for ((event, _), row) in events.into_iter().zip(rows) {
+            let mut flag = |kind, message| {
+                findings.push(Finding { row, kind, message });
+            };
+
+            let elapsed = event
+                .duration
+                .num_microseconds()
+                .expect(COMPLAIN_ABOUT_TRACE_SIZE);
+            if let Some(last) = last_elapsed.insert((event.source, event.span_id), elapsed) {
+                let gap = elapsed - last;
+                if gap < 0 {
+                    flag(
+                        LintKind::ElapsedBackwards,
+                        format!(
+                            "went back {} since the previous event on this span",
+                            format_duration(config, -gap)
+                        ),
+                    );
+                } else if gap >= *config.max_gap {
+                    flag(
+                        LintKind::Gap,
+                        format!(
+                            "waited {} since the previous event on this span",
+                            format_duration(config, gap)
+                        ),
+                    );
+                }
+            }
+
+            let activity = event.activity.to_lowercase();
+            if activity.contains("throttl") && !activity.contains("do not delay") {
+                flag(LintKind::Throttling, event.activity.clone());
+            }
+            if activity.contains("speculat") {
+                flag(LintKind::SpeculativeRetry, event.activity.clone());
+            }
+            if activity.contains("timeout") || activity.contains("timed out") {
+                flag(LintKind::Timeout, event.activity.clone());
+            }
+            if let Some((_, dead)) = event
+                .activity
+                .strip_prefix("Creating write handler with live: ")
+                .and_then(|rest| rest.split_once(" dead: "))
+            {
+                let dead = parse_ips(dead);
+                if !dead.is_empty() {
+                    let dead: Vec<String> = dead.iter().map(|ip| ip.to_string()).collect();
+                    flag(
+                        LintKind::DeadReplica,
+                        format!("{} down for this write", dead.join(", ")),
+                    );
+                }
+            }
+            if activity.contains("forward") || activity.contains("remote dc") {
+                flag(LintKind::CrossDc, event.activity.clone());
+            }
+        }
+
+        findings
+    }
+
+    /// Writes the findings shown after the chart with `--lint`.
+    pub(crate) fn display_lint(
+        &self,
+        cli: &Cli,
+        w: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let color = cli.color.enabled();
+        let findings = self.lint(cli);
+        let row_width = self.event_count().to_string().len().max("row".len());
+
+        writeln!(w)?;
+        let headers = format!("{:>row_width$} {:17} finding", "row", "kind");
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+        for finding in &findings {
+            let line = format!(
+                "{:>row_width$} {:17} {}",
+                finding.row,
+                finding.kind.to_string(),
+                finding.message
+            );
+            writeln!(w, "{}", color::paint(&line, SLOW, color))?;
+        }
+
+        Ok(())
+    }
+}
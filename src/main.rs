@@ -1,22 +1,303 @@
 use clap::Parser;
-use seella::{session_from_csv, Cli, OperationMode, session_from_db};
+use seella::{
+    browse, display_profiles, display_slow_log, display_stats, display_top, export_from_db,
+    find_slow_log_entry, profiles, query_stats, session_from_archive, session_from_cqlsh,
+    session_from_csv_options, session_from_db, sessions_from_archive, sessions_from_csv_options,
+    sessions_from_db, sessions_from_db_poll, slow_log_from_csv_options, slow_log_from_db, top, Cli,
+    DbAction, DbConfig, DbDriverOptions, DbFollowSource, DbParsingError, DbSource, FollowOptions,
+    OperationMode, Profile, Redactor, RowError, Session, SessionFilter, SessionsSource,
+    SlowLogRecord, SlowLogSource,
+};
+use std::{io::Write, net::SocketAddr, time::Duration};
+use terminal_size::{terminal_size, Width};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-
-    let s = match cli.mode {
-        OperationMode::Csv(ref options) => session_from_csv(
-            &options.sessions_path,
-            &options.events_path,
-            &options.session_id,
-        )?,
-        OperationMode::Db(ref options) => {
-            session_from_db(*options.addr, &options.session_id).await?
+async fn main() {
+    if let Err(err) = run().await {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cli = Cli::parse();
+    cli.color = cli.color.resolve();
+
+    // The tui and diff load their sessions from the same sources as the other modes
+    let (mut mode, tui, other) = match cli.mode {
+        OperationMode::Tui(ref options) => {
+            (OperationMode::from(options.source.clone()), true, None)
         }
+        OperationMode::Diff(ref options) => {
+            let (before, after) = options.source.sources();
+            (
+                OperationMode::from(before),
+                false,
+                Some(OperationMode::from(after)),
+            )
+        }
+        ref mode => (mode.clone(), false, None),
     };
+    let mut db = DbConnection::default();
+
+    if let OperationMode::Db(ref options) = mode {
+        if let Some(DbAction::Export(ref export)) = options.action {
+            let count = export_from_db(
+                &db.get(*options.addr, &options.driver).await?,
+                &export.session_ids,
+                &export.output,
+                cli.redact,
+            )
+            .await?;
+            eprintln!("Exported {count} sessions to {}", export.output);
+            return Ok(());
+        }
+        if let Some(DbAction::Follow(ref follow)) = options.action {
+            let source = db.get(*options.addr, &options.driver).await?;
+            return follow_db(cli, source, follow).await;
+        }
+    }
+
+    if let OperationMode::Stats(ref options) = mode {
+        let sessions = load_sessions(&options.source, &mut db).await?;
+        display_stats(&cli, &query_stats(&sessions), &mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    if let OperationMode::Profile(ref options) = mode {
+        let sessions = load_sessions(&options.source, &mut db).await?;
+        let profiles: Vec<Profile> = profiles(&sessions)
+            .into_iter()
+            .filter(|profile| {
+                options
+                    .query
+                    .as_ref()
+                    .is_none_or(|query| query.is_match(&profile.query))
+            })
+            .collect();
+        display_profiles(&cli, &profiles, &mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    if let OperationMode::Top(ref options) = mode {
+        let sessions = load_sessions(&options.source, &mut db).await?;
+        let mut sessions = top(
+            sessions,
+            &SessionFilter::from(&options.filter),
+            *options.count,
+        );
+        if cli.redact {
+            // Shared, so that the same node has the same address in every session
+            let mut redactor = Redactor::new();
+            for session in &mut sessions {
+                session.redact(&mut redactor);
+            }
+        }
+        let width = match cli.auto_width {
+            true => terminal_size().map(|(Width(width), _)| width.into()),
+            false => None,
+        };
+        display_top(&cli, &sessions, options.list, width, &mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    // Showing an entry of the slow log carries on as if the session had been asked for directly
+    if let OperationMode::SlowLog(ref options) = mode {
+        let mut entries = load_slow_log(&options.source, &mut db).await?;
+        let Some(ref show) = options.show else {
+            if cli.redact {
+                let mut redactor = Redactor::new();
+                for entry in &mut entries {
+                    redactor.redact_slow_log(entry);
+                }
+            }
+            display_slow_log(&cli, &entries, &mut std::io::stdout())?;
+            return Ok(());
+        };
+        let entry = find_slow_log_entry(&entries, show)
+            .ok_or_else(|| format!("couldn't find the slow log entry {show}"))?;
+        mode = options.source.with_session(&entry.session_id.to_string());
+    }
+
+    let mut s = load_session(&mode, &mut db).await?;
+
+    if let Some(other) = other {
+        let mut other = load_session(&other, &mut db).await?;
+        if cli.redact {
+            // Shared, so that the same node has the same address in both
+            let mut redactor = Redactor::new();
+            s.redact(&mut redactor);
+            other.redact(&mut redactor);
+        }
+        s.diff(&other).display(&cli, &mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    if cli.redact {
+        s.redact(&mut Redactor::new());
+    }
+
+    if tui {
+        return Ok(browse(&s, &cli)?);
+    }
+
+    if cli.auto_width {
+        if let Some((Width(width), _)) = terminal_size() {
+            s.fit_to_width(&mut cli, width.into());
+        }
+    }
 
     s.display(cli, &mut std::io::stdout())?;
 
     Ok(())
 }
+
+/// The connection to the database, made the first time one of the data sources needs it and then shared by every
+/// later load, so that diffing two sessions or showing an entry of the slow log only connects once.
+#[derive(Default)]
+struct DbConnection(Option<DbSource>);
+
+impl DbConnection {
+    async fn get(
+        &mut self,
+        addr: SocketAddr,
+        driver: &DbDriverOptions,
+    ) -> Result<DbSource, DbParsingError> {
+        if let Some(source) = &self.0 {
+            return Ok(source.clone());
+        }
+
+        let source = DbSource::connect(addr, DbConfig::from(driver)).await?;
+        self.0 = Some(source.clone());
+        Ok(source)
+    }
+}
+
+/// Polls the database for new sessions, showing each one that matches the filters in full as it's read.
+async fn follow_db(
+    cli: Cli,
+    source: DbSource,
+    options: &FollowOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let filter = SessionFilter::from(&options.filter);
+    let mut source = DbFollowSource::new(source, options.slow_log);
+    // Shared, so that the same node has the same address in every session
+    let mut redactor = Redactor::new();
+    let mut stdout = std::io::stdout();
+
+    loop {
+        // A timeout or a node going away shouldn't end the whole follow, so just try again next time
+        let sessions = match sessions_from_db_poll(&mut source).await {
+            Ok(sessions) => sessions,
+            Err(err) => {
+                eprintln!("warning: couldn't check for new sessions, trying again: {err}");
+                Vec::new()
+            }
+        };
+
+        for mut s in sessions {
+            if !filter.matches(&s) {
+                continue;
+            }
+            if cli.redact {
+                s.redact(&mut redactor);
+            }
+
+            let mut cli = cli.clone();
+            if cli.auto_width {
+                if let Some((Width(width), _)) = terminal_size() {
+                    s.fit_to_width(&mut cli, width.into());
+                }
+            }
+            s.display(cli, &mut stdout)?;
+            writeln!(stdout)?;
+            stdout.flush()?;
+        }
+
+        tokio::time::sleep(Duration::from_secs(*options.interval)).await;
+    }
+}
+
+/// Loads the session from any of the data sources.
+async fn load_session(
+    mode: &OperationMode,
+    db: &mut DbConnection,
+) -> Result<Session, Box<dyn std::error::Error>> {
+    let session = match mode {
+        OperationMode::Csv(options) => {
+            let (session, skipped) = session_from_csv_options(options)?;
+            warn_skipped(&skipped);
+            session
+        }
+        // clap requires the session id when there's no other action
+        OperationMode::Db(options) => {
+            session_from_db(
+                &db.get(*options.addr, &options.driver).await?,
+                options.session_id.as_deref().unwrap_or_default(),
+            )
+            .await?
+        }
+        OperationMode::Cqlsh(options) => session_from_cqlsh(&options.path)?,
+        OperationMode::Archive(options) => {
+            session_from_archive(&options.path, options.session_id.as_deref())?
+        }
+        OperationMode::Tui(_)
+        | OperationMode::Diff(_)
+        | OperationMode::Stats(_)
+        | OperationMode::Profile(_)
+        | OperationMode::Top(_)
+        | OperationMode::SlowLog(_) => {
+            return Err("can't load a single session from this mode of operation".into())
+        }
+    };
+
+    Ok(session)
+}
+
+/// Reports the rows that had to be skipped in lenient mode.
+fn warn_skipped(skipped: &[RowError]) {
+    for row_error in skipped {
+        eprintln!("warning: skipping row at {row_error}");
+    }
+}
+
+/// Loads every session from any of the data sources that can read many at once.
+async fn load_sessions(
+    source: &SessionsSource,
+    db: &mut DbConnection,
+) -> Result<Vec<Session>, Box<dyn std::error::Error>> {
+    match source {
+        SessionsSource::Csv(options) => {
+            let (sessions, skipped) = sessions_from_csv_options(options)?;
+            warn_skipped(&skipped);
+            Ok(sessions)
+        }
+        SessionsSource::Db(options) => {
+            sessions_from_db(
+                &db.get(*options.addr, &options.driver).await?,
+                options.since.map(|since| *since),
+                options.until.map(|until| *until),
+            )
+            .await
+        }
+        SessionsSource::Archive(options) => sessions_from_archive(&options.path),
+    }
+}
+
+/// Loads the entries of the slow query log from any of the data sources that have it.
+async fn load_slow_log(
+    source: &SlowLogSource,
+    db: &mut DbConnection,
+) -> Result<Vec<SlowLogRecord>, Box<dyn std::error::Error>> {
+    match source {
+        SlowLogSource::Csv(options) => slow_log_from_csv_options(options),
+        SlowLogSource::Db(options) => {
+            slow_log_from_db(
+                &db.get(*options.addr, &options.driver).await?,
+                options.since.map(|since| *since),
+                options.until.map(|until| *until),
+            )
+            .await
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    color::{self, BOLD, SLOW},
+    event::{format_duration, waterfall_str},
+    Cli, Session,
+};
+use serde::Serialize;
+use std::{fmt::Display, net::IpAddr};
+
+/// The phases of a lightweight transaction, in the order that they happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaxosPhase {
+    /// Asking the replicas to promise to accept a ballot
+    Prepare,
+    /// Reading the current values to check the condition, i.e. `IF NOT EXISTS`
+    Read,
+    /// Asking the replicas to accept the proposed update
+    Accept,
+    /// Committing the accepted update
+    Learn,
+    /// Clearing out the paxos state once the update has been committed everywhere
+    Prune,
+}
+
+impl PaxosPhase {
+    /// Works out which phase an activity belongs to, if any, from the activities written by both Scylla and Cassandra.
+    pub fn of(activity: &str) -> Option<Self> {
+        let activity = activity.to_lowercase();
+
+        if activity.contains("prune") || activity.contains("pruning") {
+            Some(Self::Prune)
+        } else if activity.contains("learn") || activity.starts_with("committing") {
+            Some(Self::Learn)
+        } else if activity.contains("cas precondition")
+            || activity.starts_with("reading existing values for cas")
+        {
+            Some(Self::Read)
+        } else if activity.contains("accept") || activity.contains("proposing") {
+            Some(Self::Accept)
+        } else if activity.contains("prepare_ballot")
+            || activity.contains("paxos_prepare")
+            || activity.contains("promis")
+            || activity.starts_with("preparing ")
+        {
+            Some(Self::Prepare)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for PaxosPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PaxosPhase::Prepare => "prepare",
+            PaxosPhase::Read => "read",
+            PaxosPhase::Accept => "accept",
+            PaxosPhase::Learn => "learn",
+            PaxosPhase::Prune => "prune",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The phases of a lightweight transaction, as pieced together from the activities of a session; see
+/// [Session::paxos_report].
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct PaxosReport {
+    /// Only the phases that were traced, in order
+    pub phases: Vec<PaxosPhaseTiming>,
+    /// How many ballots the coordinator prepared; anything more than one is a retry
+    pub rounds: usize,
+    /// Activities showing that the transaction was held up by another, such as a ballot being rejected
+    pub contention: Vec<String>,
+}
+
+/// The events of a single phase, across every node and round.
+///
+/// Offsets are in microseconds from the start of the session, as laid out in the waterfall chart. The replicas' events
+/// are laid out under the coordinator's first event rather than alongside the coordinator's own, so the phase is timed
+/// by the coordinator where possible.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PaxosPhaseTiming {
+    pub phase: PaxosPhase,
+    pub events: usize,
+    /// The nodes that took part, in the order that they're first seen
+    pub nodes: Vec<IpAddr>,
+    /// The offset of the first event, on the coordinator if it took part
+    pub start: i64,
+    /// The offset of the end of the last event, on the coordinator if it took part
+    pub end: i64,
+    /// The durations of the events
+    pub self_time: i64,
+}
+
+/// Parts of activities that show a transaction being held up by another.
+const CONTENTION: &[&str] = &[
+    "contention",
+    "rejected",
+    "retry",
+    "retrying",
+    "pre-empted",
+    "preempted",
+    "not accepted",
+    "timed out",
+];
+
+impl Session {
+    /// Groups the events of a lightweight transaction into its paxos phases, and looks for signs of contention.
+    ///
+    /// Sessions that aren't lightweight transactions have no phases.
+    pub fn paxos_report(&self) -> PaxosReport {
+        let mut report = PaxosReport::default();
+        let mut offset = 0;
+        // The phases that the coordinator has taken part in, which are timed by its events alone
+        let mut coordinated = Vec::new();
+
+        for (event, _) in self.events() {
+            let self_time = event.durations().1;
+
+            if let Some(phase) = PaxosPhase::of(&event.activity) {
+                let on_coordinator = event.source == self.coordinator;
+
+                match report
+                    .phases
+                    .iter_mut()
+                    .find(|timing| timing.phase == phase)
+                {
+                    Some(timing) => {
+                        timing.events += 1;
+                        if !timing.nodes.contains(&event.source) {
+                            timing.nodes.push(event.source);
+                        }
+                        timing.self_time += self_time;
+
+                        if on_coordinator && !coordinated.contains(&phase) {
+                            timing.start = offset;
+                            timing.end = offset + self_time;
+                        } else if on_coordinator || !coordinated.contains(&phase) {
+                            timing.start = timing.start.min(offset);
+                            timing.end = timing.end.max(offset + self_time);
+                        }
+                    }
+                    None => report.phases.push(PaxosPhaseTiming {
+                        phase,
+                        events: 1,
+                        nodes: vec![event.source],
+                        start: offset,
+                        end: offset + self_time,
+                        self_time,
+                    }),
+                }
+
+                if on_coordinator {
+                    if !coordinated.contains(&phase) {
+                        coordinated.push(phase);
+                    }
+                    // Each round starts with a new ballot
+                    if event.activity.starts_with("Preparing ") {
+                        report.rounds += 1;
+                    }
+                }
+
+                let activity = event.activity.to_lowercase();
+                if CONTENTION.iter().any(|word| activity.contains(word)) {
+                    report
+                        .contention
+                        .push(format!("{}: {}", event.source, event.activity));
+                }
+            }
+
+            offset += self_time;
+        }
+
+        report.phases.sort_by_key(|timing| timing.phase);
+        report
+    }
+
+    /// Writes the paxos phase timeline shown after the chart with `--paxos`.
+    pub(crate) fn display_paxos_report(
+        &self,
+        cli: &Cli,
+        w: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let color = cli.color.enabled();
+        let report = self.paxos_report();
+        let s_end = self.total_duration();
+
+        writeln!(w)?;
+        let headers = format!(
+            "{:7} {:w_width$} {:>6} {:>5} {:>10} {:>10} {:>10}",
+            "phase",
+            "waterfall chart",
+            "events",
+            "nodes",
+            "start",
+            "end",
+            "time",
+            w_width = *cli.waterfall_width + 2
+        );
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+
+        for timing in &report.phases {
+            let span = timing.end - timing.start;
+            writeln!(
+                w,
+                "{:7} {} {:>6} {:>5} {:>10} {:>10} {:>10}",
+                timing.phase.to_string(),
+                waterfall_str(cli, timing.start, span, span, s_end),
+                timing.events,
+                timing.nodes.len(),
+                format_duration(cli, timing.start),
+                format_duration(cli, timing.end),
+                format_duration(cli, timing.self_time),
+            )?;
+        }
+
+        if report.rounds > 1 {
+            writeln!(
+                w,
+                "{}",
+                color::paint(
+                    &format!("warning: took {} rounds to prepare a ballot", report.rounds),
+                    SLOW,
+                    color
+                )
+            )?;
+        }
+        for activity in &report.contention {
+            writeln!(
+                w,
+                "{}",
+                color::paint(&format!("contention: {activity}"), SLOW, color)
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    cli::OutputFormat,
+    color::{self, BOLD},
+    event::{format_duration, tree_str, waterfall_str},
+    stats::{group_by_query, Percentiles},
+    Cli, Event, Session,
+};
+use serde::Serialize;
+use std::{collections::HashMap, net::IpAddr};
+
+/// Many sessions of the same query merged into a single tree, showing what a typical run looks like; see [profiles].
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Profile {
+    /// See [Session::normalised_query]
+    pub query: String,
+    pub sessions: usize,
+    pub roots: Vec<ProfileNode>,
+}
+
+/// An event as it appears across the sessions of a [Profile].
+///
+/// Events are merged when they have the same [normalised activity][Event::normalised_activity], happened on the
+/// coordinator or on a replica alike, and are in the same place in the tree; the second event with an activity under
+/// a parent is only merged with the second event with that activity under the same parent in other sessions, and so
+/// on. Durations are of the event alone, not including its children, in microseconds.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ProfileNode {
+    pub activity: String,
+    pub on_coordinator: bool,
+    /// How many of the sessions had the event
+    pub count: usize,
+    /// Across the sessions that had the event
+    pub mean: i64,
+    pub p99: i64,
+    pub children: Vec<ProfileNode>,
+    #[serde(skip)]
+    durations: Vec<i64>,
+}
+
+impl ProfileNode {
+    fn new(activity: String, on_coordinator: bool) -> Self {
+        Self {
+            activity,
+            on_coordinator,
+            count: 0,
+            mean: 0,
+            p99: 0,
+            children: Vec::new(),
+            durations: Vec::new(),
+        }
+    }
+
+    /// The mean duration of the event and of everything under it, as laid out in the waterfall chart.
+    pub fn total(&self) -> i64 {
+        self.mean + self.children.iter().map(|child| child.total()).sum::<i64>()
+    }
+
+    /// Works out the distribution of the durations once every session has been merged in.
+    fn finish(&mut self) {
+        self.count = self.durations.len();
+        self.mean = match self.count {
+            0 => 0,
+            count => self.durations.iter().sum::<i64>() / count as i64,
+        };
+        self.p99 = Percentiles::new(self.durations.clone()).p99;
+        for child in &mut self.children {
+            child.finish();
+        }
+    }
+
+    fn recurse_nodes<'a>(&'a self, vec: &mut Vec<(&'a ProfileNode, usize)>, depth: usize) {
+        vec.push((self, depth));
+        for child in &self.children {
+            child.recurse_nodes(vec, depth + 1);
+        }
+    }
+}
+
+/// Merges the events of a session into the nodes at one level of the tree.
+fn merge(nodes: &mut Vec<ProfileNode>, events: &[Event], coordinator: IpAddr) {
+    // How many events with each activity have been merged in at this level so far
+    let mut seen: HashMap<(String, bool), usize> = HashMap::new();
+    // Events that haven't been seen before go after the last one that was merged, rather than at the end
+    let mut next = 0;
+
+    for event in events {
+        let key = (event.normalised_activity(), event.source == coordinator);
+        let nth = seen.entry(key.clone()).or_default();
+        let position = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.activity == key.0 && node.on_coordinator == key.1)
+            .map(|(i, _)| i)
+            .nth(*nth);
+        *nth += 1;
+
+        let i = match position {
+            Some(i) => i,
+            None => {
+                nodes.insert(next, ProfileNode::new(key.0, key.1));
+                next
+            }
+        };
+        next = next.max(i + 1);
+
+        let node = &mut nodes[i];
+        node.durations.push(event.durations().1);
+        merge(&mut node.children, event.children(), coordinator);
+    }
+}
+
+impl Profile {
+    /// Merges sessions of the same query into a single tree.
+    pub fn new(query: String, sessions: &[&Session]) -> Self {
+        let mut roots = Vec::new();
+        for session in sessions {
+            merge(&mut roots, session.root_events(), session.coordinator);
+        }
+        for root in &mut roots {
+            root.finish();
+        }
+
+        Self {
+            query,
+            sessions: sessions.len(),
+            roots,
+        }
+    }
+
+    /// Depth-first recursion of all nodes in the tree, in the same way as [Session::events].
+    pub fn nodes(&self) -> Vec<(&ProfileNode, usize)> {
+        let mut nodes = Vec::new();
+        for root in &self.roots {
+            root.recurse_nodes(&mut nodes, 0);
+        }
+        nodes
+    }
+
+    /// The mean duration of a session, as laid out in the waterfall chart.
+    pub fn total_duration(&self) -> i64 {
+        self.roots.iter().map(|root| root.total()).sum()
+    }
+
+    /// Writes the merged tree as a waterfall chart, with the mean and 99th percentile duration of each event, and the
+    /// share of the sessions that it was seen in.
+    pub fn display(&self, cli: &Cli, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let color = cli.color.enabled();
+        let nodes = self.nodes();
+        let s_end = self.total_duration();
+        let max_depth = nodes.iter().map(|(_, depth)| *depth).max().unwrap_or(1);
+        let i_max_width = nodes.len().to_string().len();
+
+        writeln!(
+            w,
+            "{}",
+            color::paint(
+                &format!("Query: {} ({} sessions)", self.query, self.sessions),
+                BOLD,
+                color
+            )
+        )?;
+
+        writeln!(w)?;
+        let headers = format!(
+            "{:i_max_width$} {:w_width$} {:>8} {:>8} {:>5} {:tree_width$} activity",
+            "",
+            "waterfall chart",
+            "mean",
+            "p99",
+            "seen",
+            "",
+            w_width = *cli.waterfall_width + 2,
+            tree_width = max_depth + 2,
+        );
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+
+        let mut offset = 0;
+        for (i, (node, depth)) in nodes.into_iter().enumerate() {
+            writeln!(
+                w,
+                "{:i_max_width$} {} {:>8} {:>8} {:>4}% {} {}",
+                i + 1,
+                waterfall_str(cli, offset, node.mean, node.total(), s_end),
+                format_duration(cli, node.mean),
+                format_duration(cli, node.p99),
+                node.count * 100 / self.sessions.max(1),
+                tree_str(cli, depth, max_depth, !node.children.is_empty()),
+                node.activity,
+            )?;
+            offset += node.mean;
+        }
+
+        Ok(())
+    }
+}
+
+/// Merges the sessions of each query into a [Profile], with the most frequent queries first.
+pub fn profiles(sessions: &[Session]) -> Vec<Profile> {
+    group_by_query(sessions)
+        .into_iter()
+        .map(|(query, group)| Profile::new(query, &group))
+        .collect()
+}
+
+/// Writes each profile, one after another.
+pub fn display_profiles(
+    cli: &Cli,
+    profiles: &[Profile],
+    w: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    if let OutputFormat::Json = cli.format {
+        serde_json::to_writer_pretty(&mut *w, profiles)?;
+        return writeln!(w);
+    }
+
+    for (i, profile) in profiles.iter().enumerate() {
+        if i > 0 {
+            writeln!(w)?;
+        }
+        profile.display(cli, w)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use crate::{
+    color::{self, BOLD, SLOW},
+    Cli, Session,
+};
+use serde::Serialize;
+use std::{collections::BTreeSet, net::IpAddr};
+
+/// What each replica read, as pieced together from the activities of a session; see [Session::read_diagnostics].
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ReadDiagnostics {
+    pub nodes: Vec<NodeReads>,
+    /// Replicas that read too many tombstones or sstables, according to `--max-tombstone-ratio` and `--max-sstables`
+    pub warnings: Vec<String>,
+}
+
+/// What a single node read, from activities such as:
+///
+/// ```text
+/// Page stats: 1 partition(s), 0 static row(s) (0 live, 0 dead), 3 clustering row(s) (2 live, 1 dead) and 0 range tombstone(s)
+/// Read 2 live rows and 1 tombstone cells
+/// Reading key {key: pk{000400000000}, token: -3485513579396041028} from sstable /var/lib/scylla/data/k/t/me-1-big-Data.db
+/// Merged data from memtables and 2 sstables
+/// Row cache miss
+/// Digest mismatch: ...
+/// ```
+#[derive(Debug, PartialEq, Serialize)]
+pub struct NodeReads {
+    pub source: IpAddr,
+    pub partitions: u64,
+    pub live_rows: u64,
+    /// Dead rows, tombstone cells and range tombstones
+    pub tombstones: u64,
+    /// The number of distinct sstables read from
+    pub sstables: usize,
+    /// Lookups in the row cache, whether or not they found anything
+    pub cache_reads: u64,
+    /// Lookups in the row cache that had to go on to the sstables, counted once however many sstables were read
+    pub cache_misses: u64,
+    pub digest_mismatches: u64,
+    pub read_repairs: u64,
+}
+
+impl NodeReads {
+    fn new(source: IpAddr) -> Self {
+        Self {
+            source,
+            partitions: 0,
+            live_rows: 0,
+            tombstones: 0,
+            sstables: 0,
+            cache_reads: 0,
+            cache_misses: 0,
+            digest_mismatches: 0,
+            read_repairs: 0,
+        }
+    }
+
+    /// The share of the rows read that were tombstones.
+    pub fn tombstone_ratio(&self) -> f64 {
+        match self.live_rows + self.tombstones {
+            0 => 0.0,
+            total => self.tombstones as f64 / total as f64,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::new(self.source)
+    }
+}
+
+impl Session {
+    /// Sums up what each node read, in the order that they're first seen, leaving out nodes that didn't read anything.
+    ///
+    /// Both the activities written by Scylla and those written by Cassandra are understood.
+    pub fn read_diagnostics(&self, config: &Cli) -> ReadDiagnostics {
+        // Alongside the names of the sstables each node read from, and whether its last cache lookup has yet to be
+        // followed by a read from an sstable
+        let mut nodes: Vec<(NodeReads, BTreeSet<&str>, bool)> = Vec::new();
+
+        for (event, _) in self.events() {
+            let (node, sstables, cache_lookup) = match nodes
+                .iter_mut()
+                .position(|(n, ..)| n.source == event.source)
+            {
+                Some(i) => &mut nodes[i],
+                None => {
+                    nodes.push((NodeReads::new(event.source), BTreeSet::new(), false));
+                    nodes.last_mut().unwrap()
+                }
+            };
+            let activity = event.activity.as_str();
+            let lowercase = activity.to_lowercase();
+
+            if activity.starts_with("Page stats: ") {
+                // Partitions, static rows (live, dead), clustering rows (live, dead) and range tombstones
+                if let [partitions, _, static_live, static_dead, _, live, dead, range_tombstones] =
+                    numbers(activity)[..]
+                {
+                    node.partitions += partitions;
+                    node.live_rows += static_live + live;
+                    node.tombstones += static_dead + dead + range_tombstones;
+                }
+            } else if activity.starts_with("Read ") && activity.ends_with(" tombstone cells") {
+                if let [live, tombstones] = numbers(activity)[..] {
+                    node.live_rows += live;
+                    node.tombstones += tombstones;
+                }
+            } else if activity.starts_with("Merged data from memtables and ") {
+                if let [count] = numbers(activity)[..] {
+                    node.sstables = node.sstables.max(count as usize);
+                }
+            }
+
+            if let Some((_, sstable)) = activity
+                .split_once(" from sstable ")
+                .or_else(|| activity.split_once(" for sstable "))
+            {
+                sstables.insert(sstable.split_whitespace().next().unwrap_or_default());
+                // Scylla only goes to the sstables for what isn't in the cache, however many sstables that takes
+                if activity.starts_with("Reading ") && *cache_lookup {
+                    node.cache_misses += 1;
+                    *cache_lookup = false;
+                }
+            }
+
+            if activity.starts_with("Querying cache")
+                || activity.starts_with("Scanning cache")
+                || lowercase.starts_with("row cache hit")
+            {
+                node.cache_reads += 1;
+                *cache_lookup = true;
+            } else if lowercase.starts_with("row cache miss") {
+                node.cache_reads += 1;
+                node.cache_misses += 1;
+            }
+
+            if lowercase.contains("digest mismatch") {
+                node.digest_mismatches += 1;
+            }
+            if lowercase.contains("read repair") || lowercase.contains("read-repair") {
+                node.read_repairs += 1;
+            }
+
+            node.sstables = node.sstables.max(sstables.len());
+        }
+
+        let nodes: Vec<NodeReads> = nodes
+            .into_iter()
+            .map(|(node, ..)| node)
+            .filter(|node| !node.is_empty())
+            .collect();
+
+        let mut warnings = Vec::new();
+        for node in &nodes {
+            if node.tombstones > 0 && node.tombstone_ratio() > *config.max_tombstone_ratio {
+                warnings.push(format!(
+                    "{} read {} tombstones for {} live rows ({:.0}%)",
+                    node.source,
+                    node.tombstones,
+                    node.live_rows,
+                    node.tombstone_ratio() * 100.0
+                ));
+            }
+            if node.sstables > *config.max_sstables {
+                warnings.push(format!(
+                    "{} read from {} sstables",
+                    node.source, node.sstables
+                ));
+            }
+        }
+
+        ReadDiagnostics { nodes, warnings }
+    }
+
+    /// Writes the read diagnostics shown after the chart with `--reads`.
+    pub(crate) fn display_read_diagnostics(
+        &self,
+        cli: &Cli,
+        w: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let color = cli.color.enabled();
+        let diagnostics = self.read_diagnostics(cli);
+        let row = |cells: [&str; 9]| {
+            format!(
+                "{:15} {:>10} {:>10} {:>10} {:>8} {:>11} {:>12} {:>17} {:>12}",
+                cells[0],
+                cells[1],
+                cells[2],
+                cells[3],
+                cells[4],
+                cells[5],
+                cells[6],
+                cells[7],
+                cells[8]
+            )
+        };
+
+        writeln!(w)?;
+        let headers = row([
+            "node",
+            "partitions",
+            "live rows",
+            "tombstones",
+            "sstables",
+            "cache reads",
+            "cache misses",
+            "digest mismatches",
+            "read repairs",
+        ]);
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+        for node in &diagnostics.nodes {
+            writeln!(
+                w,
+                "{}",
+                row([
+                    &node.source.to_string(),
+                    &node.partitions.to_string(),
+                    &node.live_rows.to_string(),
+                    &node.tombstones.to_string(),
+                    &node.sstables.to_string(),
+                    &node.cache_reads.to_string(),
+                    &node.cache_misses.to_string(),
+                    &node.digest_mismatches.to_string(),
+                    &node.read_repairs.to_string(),
+                ])
+            )?;
+        }
+
+        for warning in &diagnostics.warnings {
+            writeln!(
+                w,
+                "{}",
+                color::paint(&format!("warning: {warning}"), SLOW, color)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The numbers within an activity, in order.
+fn numbers(activity: &str) -> Vec<u64> {
+    activity
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|number| number.parse().ok())
+        .collect()
+}
@@ -1,11 +1,11 @@
 use crate::SpanId;
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, net::IpAddr};
 use uuid::Uuid;
 
 /// The basic structure and data of a Session, before it is made into the head of a tree.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SessionRecord {
     pub session_id: Uuid,
     pub client: IpAddr,
@@ -26,7 +26,7 @@ pub struct SessionRecord {
 }
 
 /// The basic structure and data of a Event, before it is made into the leaves of a tree.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct EventRecord {
     pub session_id: Uuid,
     pub event_id: Uuid,
@@ -41,3 +41,26 @@ pub struct EventRecord {
     #[serde(default)]
     pub scylla_span_id: Option<SpanId>,
 }
+
+/// An entry of the slow query log, `system_traces.node_slow_log`, which refers to the session it was traced in.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SlowLogRecord {
+    /// When the query was started, as a timeuuid
+    pub start_time: Uuid,
+    /// The node the query was logged by
+    pub node_ip: IpAddr,
+    pub shard: i32,
+    pub command: String,
+    pub date: DateTime<Utc>,
+    /// In microseconds
+    pub duration: i32,
+    pub parameters: String,
+    pub session_id: Uuid,
+    /// The IP address of the client
+    pub source_ip: IpAddr,
+    /// A scylla set of the tables the query touched, i.e. `{'k.t'}`
+    #[serde(default)]
+    pub table_names: String,
+    #[serde(default)]
+    pub username: Option<String>,
+}
@@ -0,0 +1,390 @@
+use crate::records::{EventRecord, SessionRecord, SlowLogRecord};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+use uuid::Uuid;
+
+/// What literal values are replaced with; the same as a CQL bind marker.
+const REDACTED_LITERAL: &str = "?";
+/// What usernames are replaced with.
+const REDACTED_USERNAME: &str = "redacted";
+/// The query parameters that describe how a query was run, rather than what it was run against, and so are kept.
+const SAFE_PARAMETERS: &[&str] = &[
+    "consistency_level",
+    "serial_consistency_level",
+    "page_size",
+    "user_timestamp",
+];
+/// Tokens are 64-bit hashes of the partition key, and so run to far more digits than any count or shard number.
+const MIN_TOKEN_DIGITS: usize = 10;
+
+/// Scrubs the identifying details out of a trace, so that it can be shared with vendor support or posted publicly.
+///
+/// - Literal values within the CQL of the query are replaced with `?`.
+/// - IP addresses are replaced consistently, in the order that they're first seen, so that node A becomes
+///   `192.0.2.1`, node B becomes `192.0.2.2`, and so on. IPv6 addresses become `2001:db8::1` and so on. Both ranges
+///   are reserved for documentation and will never belong to a real node.
+/// - Tokens and IP addresses mentioned within activities are replaced in the same way.
+/// - Usernames are replaced with `redacted`.
+///
+/// The same redactor should be used for every session that is to be shared together, so that each node keeps the same
+/// address throughout.
+#[derive(Debug, Default)]
+pub struct Redactor {
+    nodes: HashMap<IpAddr, IpAddr>,
+}
+
+impl Redactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redacts a session record and the records of its events, in place.
+    pub fn redact_records(&mut self, session: &mut SessionRecord, events: &mut [EventRecord]) {
+        self.session(
+            &mut session.coordinator,
+            &mut session.client,
+            &mut session.parameters,
+            &mut session.request,
+            &mut session.username,
+        );
+
+        for event in events {
+            self.event(&mut event.source, &mut event.activity);
+        }
+    }
+
+    /// Redacts the identifying fields of a session, in place. Shared by records and [crate::Session]s alike.
+    pub(crate) fn session(
+        &mut self,
+        coordinator: &mut IpAddr,
+        client: &mut IpAddr,
+        parameters: &mut String,
+        request: &mut String,
+        username: &mut Option<String>,
+    ) {
+        *coordinator = self.ip(*coordinator);
+        *client = self.ip(*client);
+        *parameters = self.parameters(parameters);
+        *request = self.text(request);
+        *username = self.username(username.as_deref());
+    }
+
+    /// Redacts the identifying fields of an event, in place. Shared by records and [crate::Event]s alike.
+    pub(crate) fn event(&mut self, source: &mut IpAddr, activity: &mut String) {
+        *source = self.ip(*source);
+        *activity = self.text(activity);
+    }
+
+    /// Redacts an entry of the slow query log, in place.
+    pub fn redact_slow_log(&mut self, entry: &mut SlowLogRecord) {
+        entry.node_ip = self.ip(entry.node_ip);
+        entry.source_ip = self.ip(entry.source_ip);
+        entry.parameters = self.parameters(&entry.parameters);
+        entry.username = self.username(entry.username.as_deref());
+    }
+
+    /// The anonymised address for the given IP address.
+    pub fn ip(&mut self, ip: IpAddr) -> IpAddr {
+        let node = self.nodes.len() as u32 + 1;
+        *self.nodes.entry(ip).or_insert_with(|| match ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(
+                u32::from(Ipv4Addr::new(192, 0, 2, 0)) + node,
+            )),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(
+                u128::from(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0)) + u128::from(node),
+            )),
+        })
+    }
+
+    pub fn username(&self, username: Option<&str>) -> Option<String> {
+        username.map(|_| String::from(REDACTED_USERNAME))
+    }
+
+    /// Redacts the `parameters` map of a session, i.e.
+    ///
+    /// ```text
+    /// {'consistency_level': 'ONE', 'query': 'INSERT INTO k.t (pk, v) VALUES (0, ''val0'');'}
+    /// ```
+    ///
+    /// becomes
+    ///
+    /// ```text
+    /// {'consistency_level': 'ONE', 'query': 'INSERT INTO k.t (pk, v) VALUES (?, ?);'}
+    /// ```
+    ///
+    /// The query has its literals replaced, and any parameters other than those describing how the query was run,
+    /// such as the bound values of a prepared statement, are replaced entirely. If the map can't be understood then
+    /// nothing of it is kept.
+    pub fn parameters(&self, parameters: &str) -> String {
+        if parameters.trim().is_empty() {
+            return parameters.to_string();
+        }
+
+        let Some((style, pairs)) = parse_map(parameters) else {
+            return String::from(REDACTED_LITERAL);
+        };
+
+        let pairs: Vec<String> = pairs
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match key.as_str() {
+                    "query" => redact_cql(&value),
+                    key if SAFE_PARAMETERS.contains(&key) => value,
+                    _ => String::from(REDACTED_LITERAL),
+                };
+                format!("{}: {}", style.quote(&key), style.quote(&value))
+            })
+            .collect();
+
+        format!("{{{}}}", pairs.join(", "))
+    }
+
+    /// Redacts free text such as an activity, replacing IP addresses, tokens and quoted strings.
+    pub fn text(&mut self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(c) = rest.chars().next() {
+            if !output.ends_with(is_word_char) {
+                if c == '\'' {
+                    output.push_str(REDACTED_LITERAL);
+                    rest = split_quoted(rest, '\'').1;
+                    continue;
+                }
+
+                if let Some((ip, remaining)) = split_ip(rest) {
+                    output.push_str(&self.ip(ip).to_string());
+                    rest = remaining;
+                    continue;
+                }
+
+                let (word, remaining) = split_while(rest, is_word_char);
+                if word.len() >= MIN_TOKEN_DIGITS && word.chars().all(|c| c.is_ascii_digit()) {
+                    // Tokens are signed, and the sign is part of the value
+                    if output.ends_with('-') {
+                        output.pop();
+                    }
+                    output.push_str(REDACTED_LITERAL);
+                    rest = remaining;
+                    continue;
+                }
+                if !word.is_empty() {
+                    output.push_str(word);
+                    rest = remaining;
+                    continue;
+                }
+            }
+
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+
+        output
+    }
+}
+
+/// Replaces the literal values within a CQL statement with `?`, keeping the keywords and identifiers.
+pub fn redact_cql(cql: &str) -> String {
+    let mut output = String::with_capacity(cql.len());
+    let mut rest = cql;
+
+    while let Some(c) = rest.chars().next() {
+        let at_boundary = !output.ends_with(is_word_char);
+
+        if c == '\'' {
+            output.push_str(REDACTED_LITERAL);
+            rest = split_quoted(rest, '\'').1;
+        } else if c == '"' {
+            // Quoted identifiers are kept as they are
+            let (identifier, remaining) = split_quoted(rest, '"');
+            output.push_str(identifier);
+            rest = remaining;
+        } else if rest.starts_with("$$") {
+            output.push_str(REDACTED_LITERAL);
+            rest = rest[2..]
+                .split_once("$$")
+                .map(|(_, remaining)| remaining)
+                .unwrap_or_default();
+        } else if at_boundary && is_word_char(c) {
+            let (word, remaining) = split_while(rest, is_word_char);
+
+            if rest
+                .get(..36)
+                .is_some_and(|uuid| Uuid::try_parse(uuid).is_ok())
+            {
+                output.push_str(REDACTED_LITERAL);
+                rest = &rest[36..];
+            } else if c.is_ascii_digit() {
+                // Unquoted identifiers can't start with a digit, so this is a number, possibly with a fraction or
+                // exponent, or a blob
+                let (_, remaining) = split_while(remaining, |c| {
+                    c.is_ascii_alphanumeric() || c == '.' || c == '_'
+                });
+                if output.ends_with('-') && !ends_with_operand(&output[..output.len() - 1]) {
+                    output.pop();
+                }
+                output.push_str(REDACTED_LITERAL);
+                rest = remaining;
+            } else {
+                output.push_str(word);
+                rest = remaining;
+            }
+        } else {
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    output
+}
+
+/// The CQL of the query within the `parameters` map of a session, if there is one.
+pub(crate) fn query_parameter(parameters: &str) -> Option<String> {
+    parse_map(parameters)?
+        .1
+        .into_iter()
+        .find(|(key, _)| key == "query")
+        .map(|(_, query)| query)
+}
+
+pub(crate) fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether the text ends with something that a following `-` would be subtracted from.
+fn ends_with_operand(text: &str) -> bool {
+    text.trim_end()
+        .ends_with(|c: char| is_word_char(c) || c == ')' || c == '?')
+}
+
+pub(crate) fn split_while(text: &str, predicate: impl Fn(char) -> bool) -> (&str, &str) {
+    let end = text
+        .char_indices()
+        .find(|(_, c)| !predicate(*c))
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+/// Splits an IP address from the start of the text, if there is one.
+pub(crate) fn split_ip(text: &str) -> Option<(IpAddr, &str)> {
+    let (v4, _) = split_while(text, |c| c.is_ascii_digit() || c == '.');
+    let v4 = v4.trim_end_matches('.');
+    if let Ok(ip) = v4.parse::<Ipv4Addr>() {
+        return Some((IpAddr::V4(ip), &text[v4.len()..]));
+    }
+
+    let (v6, _) = split_while(text, |c| c.is_ascii_hexdigit() || c == ':' || c == '.');
+    let v6 = v6.trim_end_matches(['.', ':']);
+    match v6.parse::<Ipv6Addr>() {
+        Ok(ip) if v6.contains(':') => Some((IpAddr::V6(ip), &text[v6.len()..])),
+        _ => None,
+    }
+}
+
+/// Splits a quoted string, escaped by doubling the quote as CQL does, from the start of the text.
+fn split_quoted(text: &str, quote: char) -> (&str, &str) {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            match chars.peek() {
+                Some((_, next)) if *next == quote => {
+                    chars.next();
+                }
+                _ => return text.split_at(i + c.len_utf8()),
+            }
+        }
+    }
+    (text, "")
+}
+
+/// How the `parameters` map was written out.
+#[derive(Debug, Clone, Copy)]
+enum MapStyle {
+    /// As cqlsh prints a `map<text, text>`, i.e. `{'key': 'value'}`, which is how it ends up in an exported CSV
+    Cql,
+    /// As Rust debug prints a `HashMap<String, String>`, i.e. `{"key": "value"}`, which is how we read it from the db
+    Debug,
+}
+
+impl MapStyle {
+    fn quote(&self, value: &str) -> String {
+        match self {
+            MapStyle::Cql => format!("'{}'", value.replace('\'', "''")),
+            MapStyle::Debug => format!("{value:?}"),
+        }
+    }
+}
+
+/// Parses the `parameters` map of a session into its pairs, keeping them in order.
+fn parse_map(text: &str) -> Option<(MapStyle, Vec<(String, String)>)> {
+    let mut rest = text.trim().strip_prefix('{')?.trim_start();
+    let style = match rest.chars().next()? {
+        '\'' => MapStyle::Cql,
+        '"' => MapStyle::Debug,
+        '}' => return Some((MapStyle::Cql, Vec::new())),
+        _ => return None,
+    };
+
+    let mut pairs = Vec::new();
+    loop {
+        let (key, remaining) = parse_string(style, rest)?;
+        rest = remaining.trim_start().strip_prefix(':')?.trim_start();
+        let (value, remaining) = parse_string(style, rest)?;
+        pairs.push((key, value));
+
+        rest = remaining.trim_start();
+        if let Some(remaining) = rest.strip_prefix(',') {
+            rest = remaining.trim_start();
+        } else if rest.strip_prefix('}')?.trim().is_empty() {
+            return Some((style, pairs));
+        } else {
+            return None;
+        }
+    }
+}
+
+fn parse_string(style: MapStyle, text: &str) -> Option<(String, &str)> {
+    match style {
+        MapStyle::Cql => {
+            let (quoted, rest) = split_quoted(text, '\'');
+            let inner = quoted.strip_prefix('\'')?.strip_suffix('\'')?;
+            Some((inner.replace("''", "'"), rest))
+        }
+        MapStyle::Debug => {
+            let mut chars = text.char_indices().skip(1);
+            let mut value = String::new();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => return Some((value, &text[i + 1..])),
+                    '\\' => match chars.next()?.1 {
+                        'n' => value.push('\n'),
+                        'r' => value.push('\r'),
+                        't' => value.push('\t'),
+                        '0' => value.push('\0'),
+                        'u' => {
+                            // \u{..}
+                            let (_, open) = chars.next()?;
+                            if open != '{' {
+                                return None;
+                            }
+                            let mut code = String::new();
+                            for (_, c) in chars.by_ref() {
+                                if c == '}' {
+                                    break;
+                                }
+                                code.push(c);
+                            }
+                            value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                        }
+                        c => value.push(c),
+                    },
+                    c => value.push(c),
+                }
+            }
+            None
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{
+    color::{self, BOLD},
+    event::format_duration,
+    Cli, Session,
+};
+use serde::Serialize;
+use std::net::IpAddr;
+
+/// Which replicas the coordinator sent requests to, and how long each took to respond, as pieced together from the
+/// activities of a session; see [Session::replica_report].
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ReplicaReport {
+    pub write_handlers: Vec<WriteHandler>,
+    pub requests: Vec<ReplicaRequest>,
+}
+
+/// The replicas chosen for a write, from:
+///
+/// ```text
+/// Creating write handler for token: -3485513579396041028 natural: {172.17.0.3} pending: {}
+/// Creating write handler with live: {172.17.0.3} dead: {}
+/// ```
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct WriteHandler {
+    pub token: Option<i64>,
+    /// The replicas that own the token
+    pub natural: Vec<IpAddr>,
+    /// The replicas that are about to own the token, such as when a node is joining
+    pub pending: Vec<IpAddr>,
+    pub live: Vec<IpAddr>,
+    /// The replicas that were down, and so weren't sent the write
+    pub dead: Vec<IpAddr>,
+}
+
+/// A request sent to a replica, and its response, from:
+///
+/// ```text
+/// Sending a mutation to /172.17.0.3
+/// Got a response from /172.17.0.3
+/// read_data: sending a message to /172.17.0.3
+/// read_data: got response from /172.17.0.3
+/// ```
+///
+/// Times are offsets in microseconds from the start of the session, as laid out in the waterfall chart. Either may be
+/// missing if the other half of the exchange wasn't traced.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReplicaRequest {
+    pub replica: IpAddr,
+    /// What was asked of the replica, i.e. `mutation` or `read_data`
+    pub verb: String,
+    pub sent_at: Option<i64>,
+    pub acked_at: Option<i64>,
+    /// The time between sending the request and getting the response, if both were traced
+    pub round_trip: Option<i64>,
+}
+
+impl Session {
+    /// Pieces together which replicas were sent requests, and when they responded, from the activities of the events.
+    ///
+    /// Each response is matched up with the earliest unanswered request of the same kind to the same replica.
+    pub fn replica_report(&self) -> ReplicaReport {
+        let mut report = ReplicaReport::default();
+        let mut offset = 0;
+
+        for (event, _) in self.events() {
+            let activity = event.activity.as_str();
+
+            if let Some(rest) = activity.strip_prefix("Creating write handler for token: ") {
+                let (token, rest) = rest.split_once(" natural: ").unwrap_or((rest, ""));
+                let (natural, pending) = rest.split_once(" pending: ").unwrap_or((rest, ""));
+                report.write_handlers.push(WriteHandler {
+                    token: token.trim().parse().ok(),
+                    natural: parse_ips(natural),
+                    pending: parse_ips(pending),
+                    ..Default::default()
+                });
+            } else if let Some(rest) = activity.strip_prefix("Creating write handler with live: ") {
+                let (live, dead) = rest.split_once(" dead: ").unwrap_or((rest, ""));
+                // Follows on from the handler for the token, unless that wasn't traced
+                if report
+                    .write_handlers
+                    .last()
+                    .is_none_or(|handler| !handler.live.is_empty() || !handler.dead.is_empty())
+                {
+                    report.write_handlers.push(WriteHandler::default());
+                }
+                let handler = report.write_handlers.last_mut().unwrap();
+                handler.live = parse_ips(live);
+                handler.dead = parse_ips(dead);
+            } else if let Some((verb, replica)) = parse_request(activity) {
+                report.requests.push(ReplicaRequest {
+                    replica,
+                    verb,
+                    sent_at: Some(offset),
+                    acked_at: None,
+                    round_trip: None,
+                });
+            } else if let Some((verb, replica)) = parse_response(activity) {
+                match report.requests.iter_mut().find(|request| {
+                    request.replica == replica && request.verb == verb && request.acked_at.is_none()
+                }) {
+                    Some(request) => {
+                        request.acked_at = Some(offset);
+                        request.round_trip = request.sent_at.map(|sent_at| offset - sent_at);
+                    }
+                    None => report.requests.push(ReplicaRequest {
+                        replica,
+                        verb,
+                        sent_at: None,
+                        acked_at: Some(offset),
+                        round_trip: None,
+                    }),
+                }
+            }
+
+            offset += event.durations().1;
+        }
+
+        report
+    }
+
+    /// Writes the replica report shown after the chart with `--replicas`.
+    pub(crate) fn display_replica_report(
+        &self,
+        cli: &Cli,
+        w: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        let color = cli.color.enabled();
+        let report = self.replica_report();
+        let join = |ips: &[IpAddr]| {
+            ips.iter()
+                .map(|ip| ip.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let time = |micros: Option<i64>| {
+            micros
+                .map(|micros| format_duration(cli, micros))
+                .unwrap_or_else(|| String::from("-"))
+        };
+
+        if !report.write_handlers.is_empty() {
+            writeln!(w)?;
+            let headers = format!(
+                "{:20} {:15} {:15} {:15} {:15}",
+                "token", "natural", "pending", "live", "dead"
+            );
+            writeln!(w, "{}", color::paint(headers.trim_end(), BOLD, color))?;
+            for handler in &report.write_handlers {
+                let line = format!(
+                    "{:20} {:15} {:15} {:15} {:15}",
+                    handler.token.map(|t| t.to_string()).unwrap_or_default(),
+                    join(&handler.natural),
+                    join(&handler.pending),
+                    join(&handler.live),
+                    join(&handler.dead),
+                );
+                writeln!(w, "{}", line.trim_end())?;
+            }
+        }
+
+        writeln!(w)?;
+        let headers = format!(
+            "{:15} {:20} {:>10} {:>10} {:>10}",
+            "replica", "request", "sent at", "acked at", "round trip"
+        );
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+        for request in &report.requests {
+            writeln!(
+                w,
+                "{:15} {:20} {:>10} {:>10} {:>10}",
+                request.replica.to_string(),
+                request.verb,
+                time(request.sent_at),
+                time(request.acked_at),
+                time(request.round_trip),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a set of addresses as written in an activity, i.e. `{172.17.0.2, 172.17.0.3}`.
+pub(crate) fn parse_ips(set: &str) -> Vec<IpAddr> {
+    set.trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect()
+}
+
+/// Parses the verb and replica of a request sent by the coordinator, i.e. `read_data: sending a message to /1.2.3.4`.
+fn parse_request(activity: &str) -> Option<(String, IpAddr)> {
+    if let Some(replica) = activity.strip_prefix("Sending a mutation to /") {
+        return Some((String::from("mutation"), replica.parse().ok()?));
+    }
+    let (verb, replica) = activity.split_once(": sending a message to /")?;
+    Some((verb.to_string(), replica.parse().ok()?))
+}
+
+/// Parses the verb and replica of a response to the coordinator, i.e. `read_data: got response from /1.2.3.4`.
+fn parse_response(activity: &str) -> Option<(String, IpAddr)> {
+    if let Some(replica) = activity.strip_prefix("Got a response from /") {
+        return Some((String::from("mutation"), replica.parse().ok()?));
+    }
+    let (verb, replica) = activity.split_once(": got response from /")?;
+    Some((verb.to_string(), replica.parse().ok()?))
+}
@@ -0,0 +1,63 @@
+use crate::{Breakdown, Cli, Finding, PaxosReport, ReadDiagnostics, ReplicaReport, Session};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// The reports asked for on the command line, written out together as JSON with `--format json`.
+///
+/// Reports that weren't asked for are left out entirely.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub session_id: Uuid,
+    pub coordinator: IpAddr,
+    pub started_at: DateTime<Utc>,
+    /// The total duration of the events, in microseconds; see [Session::total_duration]
+    pub duration: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replicas: Option<ReplicaReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reads: Option<ReadDiagnostics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paxos: Option<PaxosReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lint: Option<Vec<Finding>>,
+}
+
+/// The events summed up by node and by shard; see [Session::breakdown].
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub nodes: Vec<Breakdown>,
+    pub shards: Vec<Breakdown>,
+}
+
+impl Session {
+    /// Builds the reports asked for by the configuration.
+    pub fn report(&self, config: &Cli) -> Report {
+        Report {
+            session_id: self.id,
+            coordinator: self.coordinator,
+            started_at: self.started_at,
+            duration: self.total_duration(),
+            summary: config.summary.then(|| Summary {
+                nodes: self.breakdown(false),
+                shards: self.breakdown(true),
+            }),
+            replicas: config.replicas.then(|| self.replica_report()),
+            reads: config.reads.then(|| self.read_diagnostics(config)),
+            paxos: config.paxos.then(|| self.paxos_report()),
+            lint: config.lint.then(|| self.lint(config)),
+        }
+    }
+
+    pub(crate) fn display_json(
+        &self,
+        config: &Cli,
+        w: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut *w, &self.report(config))?;
+        writeln!(w)
+    }
+}
@@ -1,12 +1,25 @@
 use crate::{
-    event::Event,
+    cli::{ColorBy, OutputFormat},
+    color::{self, Palette, BOLD, DIM},
+    event::{slowest_span, Event},
+    filter::EventFilter,
+    group::EventGroup,
     records::{EventRecord, SessionRecord},
-    Cli,
+    Cli, MaxActivityWidth, Redactor, WaterfallWidth,
 };
 use chrono::{DateTime, Duration, Utc};
-use std::{collections::VecDeque, fmt::Debug, net::IpAddr};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    net::IpAddr,
+};
 use uuid::Uuid;
 
+/// The narrowest that [Session::fit_to_width] will make the waterfall chart, leaving room for its header.
+const MIN_WATERFALL_WIDTH: usize = 13;
+/// The narrowest that [Session::fit_to_width] will make the activity column, unless no activity needs that much.
+const MIN_ACTIVITY_WIDTH: usize = 20;
+
 /// All of the information related to a single tracing session.
 ///
 /// This is effectively:
@@ -58,14 +71,16 @@ pub struct Session {
     root_events: Vec<Event>,
 }
 
-impl Session
-{
+impl Session {
     pub(crate) fn new(session_record: SessionRecord, event_records: Vec<EventRecord>) -> Self {
         let (mut root_events, mut child_events): (VecDeque<Event>, VecDeque<Event>) = event_records
             .into_iter()
             .map(Event::from)
             .partition(|event| event.parent_span_id.is_root());
 
+        // The number of events in a row that could not be placed into the tree.
+        let mut unplaced = 0;
+
         'child_events: while let Some(child_event) = child_events.pop_front() {
             let mut opt = Some(child_event);
             '_root_search: for root_event in &mut root_events {
@@ -73,13 +88,33 @@ impl Session
                 match root_event.try_add_child(opt.take().unwrap()) {
                     // In the case that this has been handled, we want to move to the next,
                     // skipping adding it back onto the queue after the for loop below.
-                    Ok(_) => continue 'child_events,
+                    Ok(_) => {
+                        unplaced = 0;
+                        continue 'child_events;
+                    }
                     Err(child_event) => opt = Some(child_event),
                 }
             }
 
             // child event was not the child of any current root event or their children. Add it back to the queue.
             child_events.push_back(opt.take().unwrap());
+            unplaced += 1;
+
+            // Having gone through the whole queue without placing anything, the remaining events must be missing their
+            // parents, such as when rows have been skipped. Promote one to a root event rather than looping forever,
+            // preferring one whose parent isn't also waiting in the queue.
+            if unplaced >= child_events.len() {
+                let orphan = child_events
+                    .iter()
+                    .position(|event| {
+                        !child_events
+                            .iter()
+                            .any(|parent| parent.span_id == event.parent_span_id)
+                    })
+                    .unwrap_or(0);
+                root_events.extend(child_events.remove(orphan));
+                unplaced = 0;
+            }
         }
 
         Self {
@@ -118,6 +153,10 @@ impl Session
         events
     }
 
+    pub(crate) fn root_events(&self) -> &[Event] {
+        &self.root_events
+    }
+
     /// Returns the total duration of the trace.
     ///
     /// Given by summing the total durations of all root traces.
@@ -125,9 +164,185 @@ impl Session
         self.root_events.iter().map(|e| e.durations().0).sum()
     }
 
+    /// The chain of events, across the coordinator and replicas, that determined how long the session took.
+    ///
+    /// Events within a span happen one after another, but the spans started by an event, such as a request being
+    /// handled by each replica, run alongside each other, and only the slowest of those is waited for. The path follows
+    /// the slowest span at each level, so that the coordinator's events are followed by those of the replica it was
+    /// waiting on, and so on. Events are in the same depth-first order as [Session::events].
+    pub fn critical_path(&self) -> Vec<&Event> {
+        let mut path = Vec::new();
+        for event in slowest_span(&self.root_events) {
+            event.recurse_critical_path(&mut path);
+        }
+        path
+    }
+
+    /// Which of the [events][Session::events] are kept by the filter, in the same order.
+    ///
+    /// As well as the events matching the filter, their ancestors are kept to give them context.
+    pub fn filter_events(&self, filter: &EventFilter) -> Vec<bool> {
+        let events = self.events();
+        if filter.is_empty() {
+            return vec![true; events.len()];
+        }
+
+        let mut kept = vec![false; events.len()];
+        // The indices of the ancestors of the current event, one per level of depth
+        let mut ancestors: Vec<usize> = Vec::new();
+
+        for (i, (event, depth)) in events.iter().enumerate() {
+            ancestors.truncate(*depth);
+            if filter.matches(event, *depth) {
+                kept[i] = true;
+                for &ancestor in &ancestors {
+                    kept[ancestor] = true;
+                }
+            }
+            ancestors.push(i);
+        }
+
+        kept
+    }
+
+    /// Finds the runs of consecutive events that only differ by the numbers and addresses in their activities, such
+    /// as the shard readers created by a range scan, so that they can be shown as a single row.
+    ///
+    /// Only sibling events without children, from the same node, and with the same
+    /// [normalised activity][Event::normalised_activity] are grouped, and only runs of at least two events are
+    /// returned.
+    pub fn group_events(&self) -> Vec<EventGroup<'_>> {
+        let events = self.events();
+        let mut groups = Vec::new();
+        let mut i = 0;
+
+        while let Some((first, depth)) = events.get(i) {
+            let activity = first.normalised_activity();
+            let len = events[i..]
+                .iter()
+                .take_while(|(event, event_depth)| {
+                    event_depth == depth
+                        && !event.is_parent()
+                        && event.source == first.source
+                        && event.normalised_activity() == activity
+                })
+                .count();
+
+            if len >= 2 {
+                groups.push(EventGroup {
+                    index: i,
+                    events: events[i..i + len].iter().map(|(event, _)| *event).collect(),
+                    depth: *depth,
+                });
+            }
+            i += len.max(1);
+        }
+
+        groups
+    }
+
+    /// The groups that are shown as a single row, given `--group` and `--expand`.
+    pub(crate) fn collapsed_groups(&self, cli: &Cli) -> Vec<EventGroup<'_>> {
+        match cli.group {
+            true => self
+                .group_events()
+                .into_iter()
+                .filter(|group| !cli.expand.contains(&(group.index + 1)))
+                .collect(),
+            false => Vec::new(),
+        }
+    }
+
+    /// Scrubs the identifying details out of the session and all of its events, so that it can be shared.
+    ///
+    /// See [Redactor] for what is replaced.
+    pub fn redact(&mut self, redactor: &mut Redactor) {
+        redactor.session(
+            &mut self.coordinator,
+            &mut self.client,
+            &mut self.parameters,
+            &mut self.request,
+            &mut self.username,
+        );
+
+        for root_event in &mut self.root_events {
+            root_event.recurse_events_mut(&mut |event| {
+                redactor.event(&mut event.source, &mut event.activity);
+            });
+        }
+    }
+
+    /// Chooses the widths of the waterfall chart and activity column so that each line of [Session::display] fits
+    /// within the given number of columns.
+    ///
+    /// The columns that have a fixed width, along with any optional columns enabled in the config, are allocated
+    /// first. Of what remains, the activity column is given what it needs up to two thirds, and the waterfall chart
+    /// gets the rest. Activities that still don't fit are truncated with an ellipsis.
+    ///
+    /// Very narrow widths can't be honoured, as each column is given some minimum width.
+    pub fn fit_to_width(&self, cli: &mut Cli, width: usize) {
+        let events = self.events();
+        let max_depth = events.iter().map(|(_, depth)| *depth).max().unwrap_or(1);
+        let longest_activity = events
+            .iter()
+            .map(|(e, _)| e.activity.chars().count())
+            .max()
+            .unwrap_or(0);
+        // Durations in microseconds are always at least as long as those in milliseconds
+        let duration_width = events
+            .iter()
+            .map(|(e, _)| e.durations().1.to_string().len())
+            .max()
+            .unwrap_or(0)
+            .max(*cli.min_duration_width);
+
+        // index, waterfall brackets, duration, node, tree, and the spaces between each of them
+        let mut fixed = self.event_count().to_string().len() + 3 + duration_width + 1 + 15 + 1;
+        fixed += max_depth + 2 + 2;
+        if cli.show_event_id {
+            fixed += 38;
+        }
+        if cli.show_span_ids {
+            fixed += 42;
+        }
+        if cli.show_thread {
+            fixed += 1 + events
+                .iter()
+                .map(|(e, _)| e.thread.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max("thread name".len());
+        }
+
+        let remaining = width.saturating_sub(fixed);
+        let activity_width = longest_activity
+            .min(remaining * 2 / 3)
+            .max(MIN_ACTIVITY_WIDTH.min(longest_activity));
+        let waterfall_width = remaining
+            .saturating_sub(activity_width)
+            .max(MIN_WATERFALL_WIDTH);
+        // Give back anything the waterfall chart had to take to reach its minimum
+        let activity_width = activity_width
+            .min(remaining.saturating_sub(waterfall_width))
+            .max(MIN_ACTIVITY_WIDTH.min(longest_activity));
+
+        cli.waterfall_width = WaterfallWidth(waterfall_width);
+        cli.max_activity_width = MaxActivityWidth(activity_width);
+    }
+
     pub fn display(&self, cli: Cli, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        if let OutputFormat::Json = cli.format {
+            return self.display_json(&cli, w);
+        }
+
+        let color = cli.color.enabled();
+
         // Print out the session info
-        writeln!(w, "Session ID: {}", &self.id)?;
+        writeln!(
+            w,
+            "{}",
+            color::paint(&format!("Session ID: {}", &self.id), BOLD, color)
+        )?;
         writeln!(w, "{}", &self.started_at.to_rfc3339())?;
         writeln!(
             w,
@@ -155,23 +370,49 @@ impl Session
         writeln!(w, "{}", &self.request)?;
         writeln!(w, "{:?}", &self.parameters)?;
 
-        // Calculations for the waterfall boxes
+        // Calculations for the waterfall boxes, which are always scaled to the whole session, even when filtered
         let s_end = self.total_duration();
         let mut offset = 0i64;
 
         let events = self.events();
-        let a_max_width = events
-            .iter()
-            .map(|(e, _)| e.activity_length())
+        let kept = self.filter_events(&EventFilter::from(&cli));
+        let kept_events = || events.iter().zip(&kept).filter(|(_, kept)| **kept);
+        // Groups are keyed by the position of their first event
+        let mut groups: HashMap<usize, EventGroup> = self
+            .collapsed_groups(&cli)
+            .into_iter()
+            .map(|group| (group.index, group))
+            .collect();
+        let a_max_width = kept_events()
+            .map(|((e, _), _)| e.activity_length())
+            .chain(groups.values().map(|group| group.activity_length(&cli)))
             .max()
             .unwrap_or(0);
-        let max_depth = events.iter().map(|(_, depth)| *depth).max().unwrap_or(1);
+        let max_depth = kept_events()
+            .map(|((_, depth), _)| *depth)
+            .max()
+            .unwrap_or(1);
         let i_max_width = self.event_count().to_string().len();
+        let critical = match cli.critical_path {
+            true => {
+                // Both are in depth-first order, so the path can be walked alongside the events
+                let mut path = self.critical_path().into_iter().peekable();
+                events
+                    .iter()
+                    .map(|(e, _)| path.next_if(|p| std::ptr::eq(*p, *e)).is_some())
+                    .collect()
+            }
+            false => vec![true; events.len()],
+        };
+        // Events off the critical path are shaded rather than solid, so that it stands out without colour
+        let paint_bar = |waterfall: String, on_path: bool, bar_color: &str| match on_path {
+            true => color::paint(&waterfall, bar_color, color),
+            false => color::paint(&waterfall.replace('█', "░"), DIM, color),
+        };
 
         // Headers
         writeln!(w)?;
-        writeln!(
-            w,
+        let headers = format!(
             "{:i_max_width$} {:w_width$} {}",
             "",
             "waterfall chart",
@@ -188,14 +429,53 @@ impl Session
                 "thread name",
             ),
             w_width = *cli.waterfall_width + 2
-        )?;
+        );
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+
+        let mut palette = Palette::default();
+        let mut i = 0;
+        while let Some((e, depth)) = events.get(i) {
+            let mut bar_color = || match cli.color_by {
+                ColorBy::Node => palette.get(&e.source.to_string()),
+                ColorBy::Shard => palette.get(&e.thread),
+            };
+
+            if let Some(group) = groups.remove(&i) {
+                // The whole group is shown if any of its events are kept
+                let end = i + group.events.len();
+                if kept[i..end].contains(&true) {
+                    let bar_color = bar_color();
+                    writeln!(
+                        w,
+                        "{:i_max_width$} {} {}",
+                        i + 1,
+                        paint_bar(
+                            group.waterfall(&cli, offset, s_end),
+                            critical[i..end].contains(&true),
+                            bar_color
+                        ),
+                        group.display(&cli, a_max_width, max_depth)
+                    )?;
+                }
 
-        for (i, (e, depth)) in events.iter().enumerate() {
+                offset += group.total_duration();
+                i = end;
+                continue;
+            }
+
+            i += 1;
+            if !kept[i - 1] {
+                // Move the offset up for the next event
+                offset += e.durations().1;
+                continue;
+            }
+
+            let bar_color = bar_color();
             writeln!(
                 w,
                 "{:i_max_width$} {} {}",
-                i + 1,
-                e.waterfall(&cli, offset, s_end),
+                i,
+                paint_bar(e.waterfall(&cli, offset, s_end), critical[i - 1], bar_color),
                 e.display(&cli, a_max_width, *depth, max_depth)
             )?;
 
@@ -203,6 +483,22 @@ impl Session
             offset += e.durations().1;
         }
 
+        if cli.summary {
+            self.display_breakdown(&cli, w)?;
+        }
+        if cli.replicas {
+            self.display_replica_report(&cli, w)?;
+        }
+        if cli.reads {
+            self.display_read_diagnostics(&cli, w)?;
+        }
+        if cli.paxos {
+            self.display_paxos_report(&cli, w)?;
+        }
+        if cli.lint {
+            self.display_lint(&cli, w)?;
+        }
+
         Ok(())
     }
 }
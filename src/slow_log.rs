@@ -0,0 +1,83 @@
+use crate::{
+    cli::OutputFormat,
+    color::{self, BOLD},
+    event::format_duration,
+    redact::query_parameter,
+    stats::normalise_query,
+    Cli, SlowLogRecord,
+};
+use uuid::Uuid;
+
+impl SlowLogRecord {
+    /// The tables the query touched, from the set in `table_names`, i.e. `k.t`.
+    pub fn tables(&self) -> Vec<String> {
+        self.table_names
+            .trim()
+            .trim_start_matches('{')
+            .trim_end_matches('}')
+            .split(',')
+            .map(|table| table.trim().trim_matches('\'').to_string())
+            .filter(|table| !table.is_empty())
+            .collect()
+    }
+
+    /// The query with its literal values replaced by `?` and its whitespace collapsed, in the same way as
+    /// [Session::normalised_query][crate::Session::normalised_query]. Falls back to the command.
+    pub fn normalised_query(&self) -> String {
+        match query_parameter(&self.parameters) {
+            Some(query) => normalise_query(&query),
+            None => self.command.clone(),
+        }
+    }
+}
+
+/// Finds the entry given on the command line, as either its number in the list, its session id, or its start time.
+pub fn find_slow_log_entry<'a>(
+    entries: &'a [SlowLogRecord],
+    entry: &str,
+) -> Option<&'a SlowLogRecord> {
+    if let Ok(id) = Uuid::try_parse(entry) {
+        return entries
+            .iter()
+            .find(|record| record.session_id == id || record.start_time == id);
+    }
+
+    let number: usize = entry.parse().ok()?;
+    entries.get(number.checked_sub(1)?)
+}
+
+/// Writes a numbered list of the entries, so that the session of one can be shown with `--show`.
+pub fn display_slow_log(
+    cli: &Cli,
+    entries: &[SlowLogRecord],
+    w: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    if let OutputFormat::Json = cli.format {
+        serde_json::to_writer_pretty(&mut *w, entries)?;
+        return writeln!(w);
+    }
+
+    let color = cli.color.enabled();
+    let number_width = entries.len().to_string().len().max("#".len());
+    let headers = format!(
+        "{:>number_width$} {:29} {:>10} {:15} {:15} {:36} {:15} query",
+        "#", "date", "duration", "node", "client", "session id", "tables"
+    );
+    writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+    for (i, entry) in entries.iter().enumerate() {
+        writeln!(
+            w,
+            "{:>number_width$} {:29} {:>10} {:15} {:15} {:36} {:15} {}",
+            i + 1,
+            entry.date.to_rfc3339(),
+            format_duration(cli, entry.duration.into()),
+            entry.node_ip.to_string(),
+            entry.source_ip.to_string(),
+            entry.session_id.to_string(),
+            entry.tables().join(","),
+            entry.normalised_query(),
+        )?;
+    }
+
+    Ok(())
+}
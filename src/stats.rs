@@ -0,0 +1,254 @@
+use crate::{
+    cli::OutputFormat,
+    color::{self, BOLD},
+    event::format_duration,
+    redact::{query_parameter, redact_cql},
+    Cli, Session, COMPLAIN_ABOUT_TRACE_SIZE,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// The durations of every session of the same query, and of each activity within them; see [query_stats].
+#[derive(Debug, PartialEq, Serialize)]
+pub struct QueryStats {
+    /// See [Session::normalised_query]
+    pub query: String,
+    pub sessions: usize,
+    /// The `duration` of the sessions, as recorded by the coordinator
+    pub duration: Percentiles,
+    /// In the order that they were first seen
+    pub activities: Vec<ActivityStats>,
+}
+
+/// The time spent on a single [normalised activity][crate::Event::normalised_activity] across sessions.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ActivityStats {
+    pub activity: String,
+    /// How many of the sessions had the activity at all
+    pub sessions: usize,
+    /// The time each session spent on the activity, summed across its events with that activity
+    pub duration: Percentiles,
+}
+
+/// The distribution of a set of durations, in microseconds.
+///
+/// Percentiles use the nearest-rank method, so are always one of the durations rather than an interpolation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct Percentiles {
+    pub p50: i64,
+    pub p90: i64,
+    pub p99: i64,
+    pub max: i64,
+}
+
+impl Percentiles {
+    pub fn new(mut durations: Vec<i64>) -> Self {
+        durations.sort_unstable();
+        let rank = |percentile: usize| {
+            let rank = (durations.len() * percentile).div_ceil(100);
+            durations
+                .get(rank.saturating_sub(1))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        Self {
+            p50: rank(50),
+            p90: rank(90),
+            p99: rank(99),
+            max: durations.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+impl Session {
+    /// The CQL of the query, from the `parameters` of the session, if it was recorded.
+    pub fn query(&self) -> Option<String> {
+        query_parameter(&self.parameters)
+    }
+
+    /// The query with its literal values replaced by `?` and its whitespace collapsed, so that every run of the same
+    /// statement has the same text. Falls back to the `request` for sessions without a query, such as those of a
+    /// batch.
+    pub fn normalised_query(&self) -> String {
+        match self.query() {
+            Some(query) => normalise_query(&query),
+            None => self.request.clone(),
+        }
+    }
+
+    /// The keyspace and table that the query is run against, as named in the query, i.e. `k` and `t` from
+    /// `INSERT INTO k.t ...`. The keyspace is only known when the query names it, as the one in use isn't traced, and
+    /// statements on a keyspace itself, such as `CREATE KEYSPACE`, have no table.
+    pub fn keyspace_and_table(&self) -> (Option<String>, Option<String>) {
+        let Some(query) = self.query() else {
+            return (None, None);
+        };
+        let query = query.replace("\\n", "\n");
+        let mut words = query
+            .split(|c: char| c.is_whitespace() || c == '(' || c == ';')
+            .filter(|word| !word.is_empty());
+
+        while let Some(word) = words.next() {
+            let word = word.to_uppercase();
+            if !matches!(
+                word.as_str(),
+                "FROM" | "INTO" | "UPDATE" | "TABLE" | "COLUMNFAMILY" | "KEYSPACE"
+            ) {
+                continue;
+            }
+            let Some(name) =
+                words.find(|word| !matches!(word.to_uppercase().as_str(), "IF" | "NOT" | "EXISTS"))
+            else {
+                break;
+            };
+            let unquote = |name: &str| name.trim_matches('"').to_string();
+
+            return match (word.as_str(), name.split_once('.')) {
+                ("KEYSPACE", _) => (Some(unquote(name)), None),
+                (_, Some((keyspace, table))) => (Some(unquote(keyspace)), Some(unquote(table))),
+                (_, None) => (None, Some(unquote(name))),
+            };
+        }
+
+        (None, None)
+    }
+}
+
+/// Replaces the literal values of the query with `?` and collapses its whitespace; see [Session::normalised_query].
+pub(crate) fn normalise_query(query: &str) -> String {
+    // cqlsh escapes the newlines within the map when exporting it
+    redact_cql(&query.replace("\\n", "\n"))
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Groups the sessions by their [normalised query][Session::normalised_query], with the most frequent queries first.
+pub fn query_stats(sessions: &[Session]) -> Vec<QueryStats> {
+    group_by_query(sessions)
+        .into_iter()
+        .map(|(query, group)| {
+            let durations = group
+                .iter()
+                .map(|session| {
+                    session
+                        .duration
+                        .num_microseconds()
+                        .expect(COMPLAIN_ABOUT_TRACE_SIZE)
+                })
+                .collect();
+
+            QueryStats {
+                query,
+                sessions: group.len(),
+                duration: Percentiles::new(durations),
+                activities: activity_stats(&group),
+            }
+        })
+        .collect()
+}
+
+/// Groups the sessions by their [normalised query][Session::normalised_query], with the most frequent queries first.
+pub(crate) fn group_by_query(sessions: &[Session]) -> Vec<(String, Vec<&Session>)> {
+    let mut queries: Vec<(String, Vec<&Session>)> = Vec::new();
+    for session in sessions {
+        let query = session.normalised_query();
+        match queries.iter_mut().find(|(q, _)| *q == query) {
+            Some((_, group)) => group.push(session),
+            None => queries.push((query, vec![session])),
+        }
+    }
+    // Stable, so that queries seen the same number of times stay in the order they were first seen
+    queries.sort_by_key(|(_, group)| std::cmp::Reverse(group.len()));
+    queries
+}
+
+/// The time spent on each normalised activity, per session, in the order that the activities were first seen.
+fn activity_stats(sessions: &[&Session]) -> Vec<ActivityStats> {
+    let mut order: Vec<String> = Vec::new();
+    let mut durations: HashMap<String, Vec<i64>> = HashMap::new();
+
+    for session in sessions {
+        let mut per_session: HashMap<String, i64> = HashMap::new();
+        for (event, _) in session.events() {
+            let activity = event.normalised_activity();
+            if !durations.contains_key(&activity) && !per_session.contains_key(&activity) {
+                order.push(activity.clone());
+            }
+            *per_session.entry(activity).or_default() += event.durations().1;
+        }
+        for (activity, duration) in per_session {
+            durations.entry(activity).or_default().push(duration);
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|activity| {
+            let durations = durations.remove(&activity).unwrap_or_default();
+            ActivityStats {
+                activity,
+                sessions: durations.len(),
+                duration: Percentiles::new(durations),
+            }
+        })
+        .collect()
+}
+
+/// Writes the stats for each query, one after another.
+pub fn display_stats(
+    cli: &Cli,
+    stats: &[QueryStats],
+    w: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    if let OutputFormat::Json = cli.format {
+        serde_json::to_writer_pretty(&mut *w, stats)?;
+        return writeln!(w);
+    }
+
+    let color = cli.color.enabled();
+    let percentiles = |p: &Percentiles| {
+        format!(
+            "{:>10} {:>10} {:>10} {:>10}",
+            format_duration(cli, p.p50),
+            format_duration(cli, p.p90),
+            format_duration(cli, p.p99),
+            format_duration(cli, p.max),
+        )
+    };
+
+    for (i, query) in stats.iter().enumerate() {
+        if i > 0 {
+            writeln!(w)?;
+        }
+
+        writeln!(
+            w,
+            "{}",
+            color::paint(&format!("Query: {}", query.query), BOLD, color)
+        )?;
+        let headers = format!(
+            "{:>8} {:>10} {:>10} {:>10} {:>10} activity",
+            "sessions", "p50", "p90", "p99", "max"
+        );
+        writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+        writeln!(
+            w,
+            "{:>8} {} (session duration)",
+            query.sessions,
+            percentiles(&query.duration)
+        )?;
+        for activity in &query.activities {
+            writeln!(
+                w,
+                "{:>8} {} {}",
+                activity.sessions,
+                percentiles(&activity.duration),
+                activity.activity
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::{
+    cli::OutputFormat,
+    color::{self, BOLD},
+    event::format_duration,
+    Cli, Report, Session, SessionFilter, COMPLAIN_ABOUT_TRACE_SIZE,
+};
+
+/// Picks out the sessions that match the filter, and keeps the `count` with the longest `duration`, slowest first.
+pub fn top(mut sessions: Vec<Session>, filter: &SessionFilter, count: usize) -> Vec<Session> {
+    sessions.retain(|session| filter.matches(session));
+    // Stable, so that sessions that took as long as each other stay in the order they were read
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.duration));
+    sessions.truncate(count);
+    sessions
+}
+
+/// Writes a ranking of the sessions, followed by each of them in full unless `list_only` is set.
+///
+/// When given the width of the terminal, each session is fitted to it in turn, as with `--auto-width`.
+pub fn display_top(
+    cli: &Cli,
+    sessions: &[Session],
+    list_only: bool,
+    terminal_width: Option<usize>,
+    w: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    if let OutputFormat::Json = cli.format {
+        let reports: Vec<Report> = sessions.iter().map(|s| s.report(cli)).collect();
+        serde_json::to_writer_pretty(&mut *w, &reports)?;
+        return writeln!(w);
+    }
+
+    let color = cli.color.enabled();
+    let rank_width = sessions.len().to_string().len().max("#".len());
+    let headers = format!(
+        "{:>rank_width$} {:36} {:29} {:>10} {:15} query",
+        "#", "session id", "started at", "duration", "coordinator"
+    );
+    writeln!(w, "{}", color::paint(&headers, BOLD, color))?;
+    for (i, session) in sessions.iter().enumerate() {
+        writeln!(
+            w,
+            "{:>rank_width$} {:36} {:29} {:>10} {:15} {}",
+            i + 1,
+            session.id.to_string(),
+            session.started_at.to_rfc3339(),
+            format_duration(
+                cli,
+                session
+                    .duration
+                    .num_microseconds()
+                    .expect(COMPLAIN_ABOUT_TRACE_SIZE)
+            ),
+            session.coordinator.to_string(),
+            session.normalised_query(),
+        )?;
+    }
+
+    if list_only {
+        return Ok(());
+    }
+
+    for session in sessions {
+        writeln!(w)?;
+        let mut cli = cli.clone();
+        if let Some(width) = terminal_width {
+            session.fit_to_width(&mut cli, width);
+        }
+        session.display(cli, w)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,325 @@
+//! An interactive browser for a single [Session], for traces too long to make sense of in a one-shot printout.
+
+mod ui;
+
+use crate::{cli::DurationFormat, Cli, Event, Session};
+use ratatui::crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::collections::HashSet;
+
+/// Browses the session in the terminal until the user quits.
+pub fn browse(session: &Session, cli: &Cli) -> std::io::Result<()> {
+    let mut browser = Browser::new(session, cli.duration_format.clone());
+    let mut terminal = ratatui::init();
+
+    let result = (|| loop {
+        terminal.draw(|frame| ui::draw(frame, &mut browser))?;
+
+        if let event::Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && browser.handle_key(key) {
+                return Ok(());
+            }
+        }
+    })();
+
+    ratatui::restore();
+    result
+}
+
+/// A single event in the flattened, depth-first list of events.
+#[derive(Debug)]
+struct Row<'a> {
+    event: &'a Event,
+    depth: usize,
+    /// Microseconds from the start of the session to the start of this event, as laid out by [Session::display]
+    start: i64,
+    /// Index of the row just past the last of this event's descendants
+    end: usize,
+}
+
+/// What the keyboard is currently being used for.
+#[derive(Debug, PartialEq)]
+enum Input {
+    Normal,
+    Search(String),
+}
+
+/// The state of the interactive browser, separate from the terminal so that it can be driven directly.
+///
+/// Events are listed depth-first, as in [Session::display], and any event with children can be collapsed to hide its
+/// subtree. The waterfall chart can be zoomed into a window of time, and is always relative to the whole session
+/// rather than the selected event.
+#[derive(Debug)]
+pub struct Browser<'a> {
+    session: &'a Session,
+    rows: Vec<Row<'a>>,
+    /// Indices of the rows currently shown, skipping the descendants of collapsed rows
+    visible: Vec<usize>,
+    collapsed: HashSet<usize>,
+    /// Index into `rows` of the selected event, which is always visible
+    selected: usize,
+    /// The window of the session shown in the waterfall, in microseconds from its start
+    window: (i64, i64),
+    query: String,
+    input: Input,
+    duration_format: DurationFormat,
+    /// Position in `visible` of the first row on screen, kept between draws to avoid jumping around
+    scroll: usize,
+    /// How many rows fit on screen, as of the last draw
+    page: usize,
+}
+
+impl<'a> Browser<'a> {
+    pub fn new(session: &'a Session, duration_format: DurationFormat) -> Self {
+        let events = session.events();
+        let mut rows: Vec<Row> = Vec::with_capacity(events.len());
+        let mut open: Vec<usize> = Vec::new();
+        let mut offset = 0;
+
+        for (i, (event, depth)) in events.into_iter().enumerate() {
+            while open
+                .last()
+                .is_some_and(|&parent| rows[parent].depth >= depth)
+            {
+                rows[open.pop().unwrap()].end = i;
+            }
+            open.push(i);
+
+            rows.push(Row {
+                event,
+                depth,
+                start: offset,
+                end: i + 1,
+            });
+            offset += event.durations().1;
+        }
+        for i in open {
+            rows[i].end = rows.len();
+        }
+
+        let mut browser = Self {
+            session,
+            rows,
+            visible: Vec::new(),
+            collapsed: HashSet::new(),
+            selected: 0,
+            window: (0, session.total_duration().max(1)),
+            query: String::new(),
+            input: Input::Normal,
+            duration_format,
+            scroll: 0,
+            page: 1,
+        };
+        browser.update_visible();
+        browser
+    }
+
+    /// The events currently listed, along with their depth in the tree.
+    pub fn visible_events(&self) -> Vec<(&'a Event, usize)> {
+        self.visible
+            .iter()
+            .map(|&i| (self.rows[i].event, self.rows[i].depth))
+            .collect()
+    }
+
+    /// The selected event, unless the session has no events at all.
+    pub fn selected_event(&self) -> Option<&'a Event> {
+        self.rows.get(self.selected).map(|row| row.event)
+    }
+
+    /// The window of the session shown in the waterfall, in microseconds from its start.
+    pub fn window(&self) -> (i64, i64) {
+        self.window
+    }
+
+    /// Handles a key press, returning whether the user asked to quit.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return true;
+        }
+
+        if let Input::Search(ref mut query) = self.input {
+            match key.code {
+                KeyCode::Enter => {
+                    let query = std::mem::take(query);
+                    self.input = Input::Normal;
+                    self.search(&query);
+                }
+                KeyCode::Esc => self.input = Input::Normal,
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Char(c) => query.push(c),
+                _ => {}
+            }
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            // Nothing else to do without any events
+            _ if self.rows.is_empty() => {}
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(self.page as isize),
+            KeyCode::PageUp => self.move_selection(-(self.page as isize)),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX),
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Right | KeyCode::Char('l') => self.expand(),
+            KeyCode::Char('/') => self.input = Input::Search(String::new()),
+            KeyCode::Char('n') => self.next_match(true),
+            KeyCode::Char('N') => self.next_match(false),
+            KeyCode::Tab => self.next_node(true),
+            KeyCode::BackTab => self.next_node(false),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.zoom(0.5),
+            KeyCode::Char('-') => self.zoom(2.0),
+            KeyCode::Char('z') => self.zoom_to_selected(),
+            KeyCode::Char('0') => self.reset_zoom(),
+            _ => {}
+        }
+
+        false
+    }
+
+    /// Moves the selection up or down the visible events, stopping at either end.
+    pub fn move_selection(&mut self, by: isize) {
+        let position = self.position() as isize;
+        let last = self.visible.len().saturating_sub(1) as isize;
+        let position = position.saturating_add(by).clamp(0, last) as usize;
+        self.selected = self.visible[position];
+    }
+
+    /// Collapses the selected event if it is expanded, otherwise expands it.
+    pub fn toggle(&mut self) {
+        if !self.collapsed.remove(&self.selected) && self.has_children(self.selected) {
+            self.collapsed.insert(self.selected);
+        }
+        self.update_visible();
+    }
+
+    /// Collapses the selected event, or if there's nothing to collapse, moves to its parent.
+    pub fn collapse(&mut self) {
+        if self.has_children(self.selected) && self.collapsed.insert(self.selected) {
+            self.update_visible();
+        } else if let Some(parent) = self.parent(self.selected) {
+            self.selected = parent;
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if self.collapsed.remove(&self.selected) {
+            self.update_visible();
+        }
+    }
+
+    /// Selects the next event, after the selected one, whose activity contains the query, ignoring case.
+    ///
+    /// An empty query clears the search.
+    pub fn search(&mut self, query: &str) {
+        self.query = query.to_lowercase();
+        self.next_match(true);
+    }
+
+    /// Selects the next or previous match of the last search, wrapping around, and expanding its ancestors if needed.
+    pub fn next_match(&mut self, forwards: bool) {
+        if self.query.is_empty() {
+            return;
+        }
+        let query = self.query.clone();
+        self.select_next_where(forwards, |row| {
+            row.event.activity.to_lowercase().contains(&query)
+        });
+    }
+
+    /// Selects the next or previous event that ran on a different node to the selected one.
+    pub fn next_node(&mut self, forwards: bool) {
+        let source = self.rows[self.selected].event.source;
+        self.select_next_where(forwards, |row| row.event.source != source);
+    }
+
+    /// Narrows or widens the waterfall window by the given factor, keeping the start of the selected event in place.
+    pub fn zoom(&mut self, factor: f64) {
+        let total = self.session.total_duration().max(1);
+        let (start, end) = self.window;
+        let anchor = self.rows[self.selected].start.clamp(start, end);
+
+        let width = (((end - start) as f64 * factor).round() as i64).clamp(1, total);
+        let before = ((anchor - start) as f64 * factor).round() as i64;
+        let start = (anchor - before).clamp(0, total - width);
+        self.window = (start, start + width);
+    }
+
+    /// Zooms the waterfall to the selected event and its children.
+    pub fn zoom_to_selected(&mut self) {
+        let row = &self.rows[self.selected];
+        self.window = (row.start, row.start + row.event.durations().0.max(1));
+    }
+
+    pub fn reset_zoom(&mut self) {
+        self.window = (0, self.session.total_duration().max(1));
+    }
+
+    fn select_next_where(&mut self, forwards: bool, predicate: impl Fn(&Row) -> bool) {
+        let count = self.rows.len();
+        let found = (1..count)
+            .map(|step| match forwards {
+                true => (self.selected + step) % count,
+                false => (self.selected + count - step) % count,
+            })
+            .find(|&i| predicate(&self.rows[i]));
+
+        if let Some(i) = found {
+            self.reveal(i);
+            self.selected = i;
+        }
+    }
+
+    /// Expands every ancestor of the row so that it is visible.
+    fn reveal(&mut self, row: usize) {
+        let mut ancestor = self.parent(row);
+        while let Some(i) = ancestor {
+            self.collapsed.remove(&i);
+            ancestor = self.parent(i);
+        }
+        self.update_visible();
+    }
+
+    fn parent(&self, row: usize) -> Option<usize> {
+        (0..row)
+            .rev()
+            .find(|&i| self.rows[i].depth < self.rows[row].depth && self.rows[i].end > row)
+    }
+
+    fn has_children(&self, row: usize) -> bool {
+        self.rows[row].end > row + 1
+    }
+
+    /// Position of the selected event within the visible events.
+    fn position(&self) -> usize {
+        self.visible
+            .iter()
+            .position(|&i| i == self.selected)
+            .unwrap_or(0)
+    }
+
+    fn update_visible(&mut self) {
+        self.visible.clear();
+        let mut i = 0;
+        while i < self.rows.len() {
+            self.visible.push(i);
+            i = match self.collapsed.contains(&i) {
+                true => self.rows[i].end,
+                false => i + 1,
+            };
+        }
+
+        // The selected event may have just been hidden inside a collapsed ancestor
+        while !self.visible.contains(&self.selected) {
+            match self.parent(self.selected) {
+                Some(parent) => self.selected = parent,
+                None => break,
+            }
+        }
+    }
+}
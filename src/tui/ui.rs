@@ -0,0 +1,208 @@
+use super::{Browser, Input};
+use crate::cli::DurationFormat;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use std::collections::HashMap;
+
+/// The colours used to tell nodes apart, matching those of the text output.
+const PALETTE: &[Color] = &[
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+    Color::LightCyan,
+    Color::LightMagenta,
+    Color::LightYellow,
+    Color::LightGreen,
+];
+
+const HELP: &str =
+    "q quit  ↑↓ move  ⏎ collapse/expand  ←→ collapse/expand  / search  n/N next/prev match  \
+                    tab next node  +/- zoom  z zoom to event  0 reset zoom";
+
+pub(super) fn draw(frame: &mut Frame, browser: &mut Browser) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [list, detail] =
+        Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)]).areas(body);
+
+    draw_header(frame, header, browser);
+    draw_list(frame, list, browser);
+    draw_detail(frame, detail, browser);
+
+    let footer_text = match browser.input {
+        Input::Search(ref query) => format!("/{query}"),
+        Input::Normal => String::from(HELP),
+    };
+    frame.render_widget(Paragraph::new(footer_text).dim(), footer);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, browser: &Browser) {
+    let session = browser.session;
+    let (start, end) = browser.window;
+    let lines = vec![
+        Line::from(vec![
+            Span::from(format!("Session {}", session.id)).bold(),
+            Span::from(format!(
+                "  {}  {} -> {}  {} events",
+                session.request,
+                session.client,
+                session.coordinator,
+                session.event_count()
+            )),
+        ]),
+        Line::from(format!(
+            "Showing {} to {} of {}",
+            format_duration(start, &browser.duration_format),
+            format_duration(end, &browser.duration_format),
+            format_duration(session.total_duration(), &browser.duration_format),
+        ))
+        .dim(),
+    ];
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+fn draw_list(frame: &mut Frame, area: Rect, browser: &mut Browser) {
+    let height = area.height as usize;
+    browser.page = height.max(1);
+
+    // Keep the selection on screen, only scrolling when it would otherwise fall off
+    let position = browser.position();
+    if position < browser.scroll {
+        browser.scroll = position;
+    } else if position >= browser.scroll + height {
+        browser.scroll = position + 1 - height;
+    }
+
+    let max_depth = browser.rows.iter().map(|row| row.depth).max().unwrap_or(0);
+    let waterfall_width = (area.width as usize * 2 / 5).max(10);
+
+    let mut colors = HashMap::new();
+    for row in &browser.rows {
+        let next = PALETTE[colors.len() % PALETTE.len()];
+        colors.entry(row.event.source).or_insert(next);
+    }
+
+    let lines: Vec<Line> = browser
+        .visible
+        .iter()
+        .skip(browser.scroll)
+        .take(height)
+        .map(|&i| {
+            let row = &browser.rows[i];
+            let (total, own) = row.event.durations();
+
+            let mut tree = format!("{:│>depth$}", "├", depth = row.depth + 1);
+            if browser.collapsed.contains(&i) {
+                tree.push('+');
+            } else if browser.has_children(i) {
+                tree.push('┬');
+            }
+            let tree = format!("{tree:─<width$}", width = max_depth + 2);
+
+            let line = Line::from(vec![
+                Span::styled(
+                    bar(row.start, own, total, browser.window, waterfall_width),
+                    Style::new().fg(colors[&row.event.source]),
+                ),
+                Span::from(format!(
+                    " {:>7} {:15} ",
+                    format_duration(own, &browser.duration_format),
+                    row.event.source.to_string(),
+                )),
+                Span::from(tree).dim(),
+                Span::from(format!(" {}", row.event.activity)),
+            ]);
+
+            match i == browser.selected {
+                true => line.add_modifier(Modifier::REVERSED),
+                false => line,
+            }
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+fn draw_detail(frame: &mut Frame, area: Rect, browser: &Browser) {
+    let Some(row) = browser.rows.get(browser.selected) else {
+        return;
+    };
+    let event = row.event;
+    let (total, own) = event.durations();
+    let format = &browser.duration_format;
+
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::from(format!("{name}: ")).bold(),
+            Span::from(value),
+        ])
+    };
+    let lines = vec![
+        Line::from(event.activity.clone()),
+        Line::default(),
+        field("node", event.source.to_string()),
+        field("thread", event.thread.clone()),
+        field("duration", format_duration(own, format)),
+        field("with children", format_duration(total, format)),
+        field("starts at", format_duration(row.start, format)),
+        field("children", (row.end - browser.selected - 1).to_string()),
+        field("event id", event.id.to_string()),
+        field("span id", event.span_id.to_string()),
+        field("parent span id", event.parent_span_id.to_string()),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::new().borders(Borders::LEFT).title(" event ")),
+        area,
+    );
+}
+
+/// Draws the waterfall bar for an event within the given window of the session, in the same style as
+/// [Event::waterfall][crate::Event::waterfall], though without the brackets.
+fn bar(start: i64, own: i64, total: i64, window: (i64, i64), width: usize) -> String {
+    let (window_start, window_end) = window;
+    let position = |time: i64| {
+        let scaled =
+            (time - window_start) as f64 * width as f64 / (window_end - window_start) as f64;
+        scaled.floor().clamp(0.0, width as f64) as usize
+    };
+
+    // Entirely outside of the window
+    if start >= window_end || start + total < window_start {
+        return " ".repeat(width);
+    }
+
+    let block_start = position(start).min(width - 1);
+    let block_end = position(start + own).max(block_start + 1);
+    let tail_end = position(start + total).max(block_end);
+
+    let mut bar = " ".repeat(block_start);
+    bar.push_str(&"█".repeat(block_end - block_start));
+    if tail_end > block_end {
+        bar.push_str(&"─".repeat(tail_end - block_end - 1));
+        bar.push('┤');
+    }
+    bar.push_str(&" ".repeat(width - tail_end));
+    bar
+}
+
+fn format_duration(micros: i64, format: &DurationFormat) -> String {
+    match format {
+        DurationFormat::Millis => format!("{}ms", micros / 1000),
+        DurationFormat::Micros => format!("{micros}µs"),
+    }
+}
@@ -0,0 +1,89 @@
+mod util;
+
+use seella::{
+    session_from_archive, session_from_csv, ArchiveError, ArchiveSource, ArchiveWriter, Cli,
+    CsvSource,
+};
+use std::path::PathBuf;
+use util::test_data;
+use uuid::Uuid;
+
+const SESSION_IDS: [&str; 2] = [
+    "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    "74612bf0-397b-11ee-8ca4-9688db6cc0f1",
+];
+
+/// Writes the given sessions from the test CSVs into an archive in a temporary directory.
+fn write_archive(name: &str, session_ids: &[&str]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!("seella-{}-{name}", std::process::id()));
+    let sessions_path = test_data("sessions.csv");
+    let events_path = test_data("events.csv");
+
+    let mut writer = ArchiveWriter::create(&path)?;
+    for session_id in session_ids {
+        let (session_record, event_records) =
+            CsvSource::new(&sessions_path, &events_path, Uuid::try_parse(session_id)?)
+                .get_data()?;
+        writer.write(session_record, event_records)?;
+    }
+    writer.finish()?;
+
+    Ok(path)
+}
+
+fn assert_round_trip(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let path = write_archive(name, &SESSION_IDS)?;
+
+    for session_id in SESSION_IDS {
+        let (mut output, mut expected) = (Vec::new(), Vec::new());
+        session_from_archive(&path, Some(session_id))?.display(Cli::default(), &mut output)?;
+        session_from_csv(
+            &test_data("sessions.csv"),
+            &test_data("events.csv"),
+            session_id,
+        )?
+        .display(Cli::default(), &mut expected)?;
+
+        assert_eq!(output, expected);
+    }
+
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    assert_round_trip("round-trip.jsonl")
+}
+
+#[test]
+fn compressed_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    assert_round_trip("round-trip.jsonl.gz")?;
+    assert_round_trip("round-trip.jsonl.zst")
+}
+
+#[test]
+fn session_id_is_optional_for_a_single_session() -> Result<(), Box<dyn std::error::Error>> {
+    let single = write_archive("single.jsonl", &SESSION_IDS[..1])?;
+    let multiple = write_archive("multiple.jsonl", &SESSION_IDS)?;
+    let empty = write_archive("empty.jsonl", &[])?;
+
+    let session = session_from_archive(&single, None)?;
+    assert_eq!(session.id, Uuid::try_parse(SESSION_IDS[0])?);
+
+    assert!(matches!(
+        ArchiveSource::new(&multiple, None).get_data(),
+        Err(ArchiveError::AmbiguousSession(2))
+    ));
+    assert!(matches!(
+        ArchiveSource::new(&empty, None).get_data(),
+        Err(ArchiveError::Empty)
+    ));
+
+    std::fs::remove_file(single)?;
+    std::fs::remove_file(multiple)?;
+    std::fs::remove_file(empty)?;
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+mod util;
+
+use seella::{session_from_csv, session_from_csv_options, Cli, CsvModeOptions};
+use std::path::PathBuf;
+use util::test_data;
+
+const SESSION_ID: &str = "74ff67c0-397b-11ee-8ca4-9688db6cc0f1";
+
+fn bundle_output(bundle: PathBuf) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    let (session, _) = session_from_csv_options(&CsvModeOptions {
+        session_id: SESSION_ID.to_string(),
+        bundle: Some(bundle),
+        ..Default::default()
+    })?;
+    session.display(Cli::default(), &mut output)?;
+    Ok(output)
+}
+
+fn plain_output() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    let session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        SESSION_ID,
+    )?;
+    session.display(Cli::default(), &mut output)?;
+    Ok(output)
+}
+
+#[test]
+fn directory_with_compressed_events() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(bundle_output(test_data("bundle"))?, plain_output()?);
+
+    Ok(())
+}
+
+#[test]
+fn compressed_tarball() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(bundle_output(test_data("bundle.tar.gz"))?, plain_output()?);
+
+    Ok(())
+}
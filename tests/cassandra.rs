@@ -13,6 +13,7 @@ fn basic_functionality() -> Result<(), Box<dyn std::error::Error>> {
             session_id: String::from("74ff67c0-397b-11ee-8ca4-9688db6cc0f1"),
             sessions_path: SessionsPath(test_data("cassandra_sessions.csv")),
             events_path: EventsPath(test_data("cassandra_events.csv")),
+            ..Default::default()
         }),
         ..Default::default()
     };
@@ -62,6 +63,7 @@ fn more_functionality() -> Result<(), Box<dyn std::error::Error>> {
             session_id: String::from("74ff67c0-397b-11ee-8ca4-9688db6cc0f1"),
             sessions_path: SessionsPath(test_data("cassandra_sessions.csv")),
             events_path: EventsPath(test_data("cassandra_events.csv")),
+            ..Default::default()
         }),
         waterfall_width: WaterfallWidth(50),
         show_event_id: true,
@@ -0,0 +1,71 @@
+mod util;
+
+use seella::{Cli, ColorBy, ColorChoice};
+use util::session;
+
+#[test]
+fn never_and_auto_are_plain() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+    let (mut never, mut auto, mut plain) = (Vec::new(), Vec::new(), Vec::new());
+
+    let cli = |color| Cli {
+        color,
+        slow_threshold: Some(300),
+        ..Default::default()
+    };
+    session.display(cli(ColorChoice::Never), &mut never)?;
+    session.display(cli(ColorChoice::Auto), &mut auto)?;
+    session.display(Cli::default(), &mut plain)?;
+
+    assert_eq!(never, plain);
+    assert_eq!(auto, plain);
+    assert!(!plain.contains(&0x1b));
+
+    Ok(())
+}
+
+#[test]
+fn colours_by_node_and_shard() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+
+    let mut output = Vec::new();
+    session.display(
+        Cli {
+            color: ColorChoice::Always,
+            slow_threshold: Some(300),
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+    let output = String::from_utf8(output)?;
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "\x1b[1mSession ID: 74ff67c0-397b-11ee-8ca4-9688db6cc0f1\x1b[0m"
+    );
+    // The two nodes get their own colours
+    assert!(lines[9].starts_with(" 1 \x1b[36m["));
+    assert!(lines[10].starts_with(" 2 \x1b[35m["));
+    assert!(lines[13].starts_with(" 5 \x1b[36m["));
+    // Only the last four events are slower than the threshold
+    assert_eq!(output.matches("\x1b[1;31m").count(), 4);
+    assert!(lines[17].contains("\x1b[1;31m332   \x1b[0m"));
+
+    let mut output = Vec::new();
+    session.display(
+        Cli {
+            color: ColorChoice::Always,
+            color_by: ColorBy::Shard,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+    let output = String::from_utf8(output)?;
+
+    // Three of the events ran on shard 4, and the rest on shard 0
+    assert_eq!(output.matches("\x1b[36m[").count(), 9);
+    assert_eq!(output.matches("\x1b[35m[").count(), 3);
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+mod util;
+
+use seella::{
+    parse_cqlsh_trace, session_from_cqlsh, Cli, CqlshModeOptions, CqlshParsingError, CqlshPath,
+    OperationMode,
+};
+use util::test_data;
+
+#[test]
+fn basic_functionality() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    let cli: Cli = Cli {
+        mode: OperationMode::Cqlsh(CqlshModeOptions {
+            path: CqlshPath(test_data("cqlsh_trace.txt")),
+        }),
+        ..Default::default()
+    };
+
+    if let OperationMode::Cqlsh(ref options) = cli.mode {
+        let session = session_from_cqlsh(&options.path)?;
+        session.display(cli, &mut output)?;
+    }
+
+    assert_eq!(
+        output,
+        r#"Session ID: 74ff67c0-397b-11ee-8ca4-9688db6cc0f1
+2023-08-13T01:48:10.172+00:00
+172.17.0.2      (N/A) -> 172.17.0.2     
+Request Size:  N/A
+Response Size: N/A
+Execute CQL3 query
+""
+
+   waterfall chart                                                                                        dur    node               activity                                                                                
+ 1 [█                                                                                                   ] 0      172.17.0.2      ├─ Parsing a statement                                                                     
+ 2 [█                                                                                                   ] 27     172.17.0.2      ├─ Processing a statement                                                                  
+ 3 [ ███                                                                                                ] 45     172.17.0.2      ├─ Creating write handler for token: -3485513579396041028 natural: {172.17.0.3} pending: {}
+ 4 [    ███                                                                                             ] 46     172.17.0.2      ├─ Creating write handler with live: {172.17.0.3} dead: {}                                 
+ 5 [       ███                                                                                          ] 49     172.17.0.2      ├─ Sending a mutation to /172.17.0.3                                                       
+ 6 [          █                                                                                         ] 5      172.17.0.3      ├─ Message received from /172.17.0.2                                                       
+ 7 [          ███                                                                                       ] 55     172.17.0.3      ├─ Sending mutation_done to /172.17.0.2                                                    
+ 8 [             ████                                                                                   ] 58     172.17.0.3      ├─ Mutation handling is done                                                               
+ 9 [                 ████████████████████                                                               ] 332    172.17.0.2      ├─ Got a response from /172.17.0.3                                                         
+10 [                                     █████████████████████                                          ] 333    172.17.0.2      ├─ Delay decision due to throttling: do not delay, resuming now                            
+11 [                                                          ████████████████████                      ] 339    172.17.0.2      ├─ Mutation successfully completed                                                         
+12 [                                                                              ██████████████████████] 344    172.17.0.2      ├─ Done processing - preparing a result                                                    
+"#.as_bytes());
+
+    Ok(())
+}
+
+#[test]
+fn malformed_session_id() {
+    let trace = "Tracing session: 74ff67c0-not-a-uuid\n\n activity | timestamp | source | source_elapsed | client\n";
+
+    let err = parse_cqlsh_trace(trace.as_bytes()).unwrap_err();
+    assert!(matches!(err, CqlshParsingError::SessionId { line: 1, .. }));
+}
+
+#[test]
+fn missing_session_id_is_nil() -> Result<(), Box<dyn std::error::Error>> {
+    let trace = std::fs::read_to_string(test_data("cqlsh_trace.txt"))?;
+    let table: String = trace
+        .lines()
+        .filter(|line| !line.starts_with("Tracing session:"))
+        .map(|line| format!("{line}\n"))
+        .collect();
+
+    let (session_record, event_records) = parse_cqlsh_trace(table.as_bytes())?;
+    let (_, expected_events) = parse_cqlsh_trace(trace.as_bytes())?;
+    assert!(session_record.session_id.is_nil());
+    assert_eq!(event_records.len(), expected_events.len());
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+mod util;
+
+use seella::{session_from_csv, Cli, Event};
+use std::collections::HashSet;
+use util::{range_scan, test_data};
+
+#[test]
+fn single_replica() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+
+    // With only the one replica to wait on, everything is on the critical path
+    let path = session.critical_path();
+    assert_eq!(path.len(), session.event_count());
+    assert!(path
+        .iter()
+        .zip(session.events())
+        .all(|(p, (e, _))| std::ptr::eq(*p, e)));
+
+    Ok(())
+}
+
+#[test]
+fn slowest_replica_span() -> Result<(), Box<dyn std::error::Error>> {
+    let session = range_scan()?;
+    let path = session.critical_path();
+    let coordinator = session.coordinator;
+
+    // All of the coordinator's events, and those of just one of the spans it started on the replica, along with
+    // anything that span went on to start
+    let coordinator_events = session
+        .events()
+        .iter()
+        .filter(|(e, _)| e.source == coordinator)
+        .count();
+    assert_eq!(
+        path.iter().filter(|e| e.source == coordinator).count(),
+        coordinator_events
+    );
+
+    let depths: Vec<(*const Event, usize)> = session
+        .events()
+        .into_iter()
+        .map(|(e, depth)| (e as *const Event, depth))
+        .collect();
+    let replica_spans: HashSet<_> = path
+        .iter()
+        .filter(|e| depths.contains(&(**e as *const Event, 1)))
+        .map(|e| e.span_id.to_string())
+        .collect();
+    assert_eq!(replica_spans.len(), 1);
+
+    // Which was the slowest of those spans
+    let span_total = |span_id: &str| -> i64 {
+        session
+            .events()
+            .iter()
+            .filter(|(e, depth)| *depth == 1 && e.span_id.to_string() == span_id)
+            .map(|(e, _)| e.durations().0)
+            .sum()
+    };
+    let slowest = replica_spans.iter().next().unwrap();
+    assert!(session
+        .events()
+        .iter()
+        .filter(|(_, depth)| *depth == 1)
+        .all(|(e, _)| span_total(&e.span_id.to_string()) <= span_total(slowest)));
+
+    Ok(())
+}
+
+#[test]
+fn highlighted() -> Result<(), Box<dyn std::error::Error>> {
+    let session = range_scan()?;
+    let path_len = session.critical_path().len();
+
+    let display = |cli: Cli| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut output = Vec::new();
+        session.display(cli, &mut output)?;
+        Ok(String::from_utf8(output)?
+            .lines()
+            .skip(9)
+            .map(String::from)
+            .collect())
+    };
+
+    let lines = display(Cli {
+        critical_path: true,
+        ..Default::default()
+    })?;
+    let shaded = lines.iter().filter(|line| line.contains('░')).count();
+    assert_eq!(lines.len() - shaded, path_len);
+    assert!(!lines[0].contains('░'));
+    assert!(lines[1].contains('░'));
+
+    let lines = display(Cli::default())?;
+    assert!(lines.iter().all(|line| !line.contains('░')));
+
+    Ok(())
+}
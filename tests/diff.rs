@@ -0,0 +1,200 @@
+mod util;
+
+use clap::Parser;
+use seella::{
+    session_from_csv, Change, Cli, CqlshModeOptions, CqlshPath, CsvModeOptions, DiffModeOptions,
+    DiffSource, OperationMode, OutputFormat, Session, SessionSource,
+};
+use util::test_data;
+
+fn session(session_id: &str) -> Result<Session, Box<dyn std::error::Error>> {
+    session_from_csv(
+        &test_data("diff_sessions.csv"),
+        &test_data("diff_events.csv"),
+        session_id,
+    )
+}
+
+fn sessions() -> Result<(Session, Session), Box<dyn std::error::Error>> {
+    Ok((
+        session("74ff67c0-397b-11ee-8ca4-9688db6cc0f1")?,
+        session("7d4f6b50-397b-11ee-8ca4-9688db6cc0f1")?,
+    ))
+}
+
+#[test]
+fn same_session() -> Result<(), Box<dyn std::error::Error>> {
+    let (before, _) = sessions()?;
+    let diff = before.diff(&before);
+
+    assert_eq!(diff.rows.len(), before.event_count());
+    assert!(diff
+        .rows
+        .iter()
+        .all(|row| row.change == Change::Matched && row.delta() == Some(0)));
+    assert_eq!(diff.total_delta(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn alignment() -> Result<(), Box<dyn std::error::Error>> {
+    let (before, after) = sessions()?;
+    let diff = before.diff(&after);
+
+    let changes: Vec<(Change, Option<i64>)> = diff
+        .rows
+        .iter()
+        .map(|row| (row.change, row.delta()))
+        .collect();
+    // The tokens differ, but are the same once normalised
+    assert_eq!(
+        changes,
+        [
+            (Change::Matched, Some(0)),
+            (Change::Matched, Some(0)),
+            (Change::Added, None),
+            (Change::Matched, Some(350)),
+            (Change::Matched, Some(351)),
+            (Change::Matched, Some(0)),
+            (Change::Matched, Some(0)),
+            (Change::Matched, Some(0)),
+            (Change::Matched, Some(0)),
+            (Change::Matched, Some(366)),
+            (Change::Missing, None),
+            (Change::Matched, Some(366)),
+            (Change::Matched, Some(366)),
+        ]
+    );
+    assert_eq!(diff.total_before, 1633);
+    assert_eq!(diff.total_after, 3111);
+
+    Ok(())
+}
+
+#[test]
+fn text() -> Result<(), Box<dyn std::error::Error>> {
+    let (before, after) = sessions()?;
+    let mut output = Vec::new();
+    before.diff(&after).display(&Cli::default(), &mut output)?;
+
+    assert_eq!(
+        String::from_utf8(output)?,
+        r#"Session ID: 74ff67c0-397b-11ee-8ca4-9688db6cc0f1 -> 7d4f6b50-397b-11ee-8ca4-9688db6cc0f1
+
+      before      after      delta node            activity
+           0          0         +0 172.17.0.2      ├┬─ Parsing a statement
+           5          5         +0 172.17.0.3      │├─ Message received from /172.17.0.2
++          -         12            172.17.0.3      │├─ Waiting for a free slot in the commitlog
+          55        405       +350 172.17.0.3      │├─ Sending mutation_done to /172.17.0.2
+          58        409       +351 172.17.0.3      │├─ Mutation handling is done
+          27         27         +0 172.17.0.2      ├── Processing a statement
+          45         45         +0 172.17.0.2      ├── Creating write handler for token: 5109102939284758372 natural: {172.17.0.3} pending: {}
+          46         46         +0 172.17.0.2      ├── Creating write handler with live: {172.17.0.3} dead: {}
+          49         49         +0 172.17.0.2      ├── Sending a mutation to /172.17.0.3
+         332        698       +366 172.17.0.2      ├── Got a response from /172.17.0.3
+-        333          -            172.17.0.2      ├── Delay decision due to throttling: do not delay, resuming now
+         339        705       +366 172.17.0.2      ├── Mutation successfully completed
+         344        710       +366 172.17.0.2      ├── Done processing - preparing a result
+
+Total Duration: 1633 -> 3111 (+1478)
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json() -> Result<(), Box<dyn std::error::Error>> {
+    let (before, after) = sessions()?;
+    let mut output = Vec::new();
+    before.diff(&after).display(
+        &Cli {
+            format: OutputFormat::Json,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json["total_after"], 3111);
+    assert_eq!(
+        json["rows"][2],
+        serde_json::json!({
+            "change": "added",
+            "depth": 1,
+            "source": "172.17.0.3",
+            "activity": "Waiting for a free slot in the commitlog",
+            "before": null,
+            "after": 12,
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sessions_from_the_same_source() {
+    let csv = DiffSource::Csv {
+        options: CsvModeOptions {
+            session_id: String::from("74ff67c0-397b-11ee-8ca4-9688db6cc0f1"),
+            ..Default::default()
+        },
+        after: String::from("7d4f6b50-397b-11ee-8ca4-9688db6cc0f1"),
+    };
+    assert!(matches!(
+        csv.sources(),
+        (SessionSource::Csv(before), SessionSource::Csv(after))
+            if before.session_id == "74ff67c0-397b-11ee-8ca4-9688db6cc0f1"
+                && after.session_id == "7d4f6b50-397b-11ee-8ca4-9688db6cc0f1"
+    ));
+
+    // cqlsh output only holds one session, so the other has to be a second file
+    let cqlsh = DiffSource::Cqlsh {
+        options: CqlshModeOptions::default(),
+        other_path: CqlshPath(test_data("cqlsh_trace.txt")),
+    };
+    assert!(matches!(
+        cqlsh.sources(),
+        (_, SessionSource::Cqlsh(after)) if after.path.0 == test_data("cqlsh_trace.txt")
+    ));
+}
+
+#[test]
+fn arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::try_parse_from([
+        "seella",
+        "diff",
+        "csv",
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+        "7d4f6b50-397b-11ee-8ca4-9688db6cc0f1",
+    ])?;
+    assert!(matches!(
+        cli.mode,
+        OperationMode::Diff(DiffModeOptions {
+            source: DiffSource::Csv { options, after },
+        }) if options.session_id == "74ff67c0-397b-11ee-8ca4-9688db6cc0f1"
+            && after == "7d4f6b50-397b-11ee-8ca4-9688db6cc0f1"
+    ));
+
+    // Both sessions are needed, other than for cqlsh
+    assert!(Cli::try_parse_from([
+        "seella",
+        "diff",
+        "archive",
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1"
+    ])
+    .is_err());
+    assert!(Cli::try_parse_from(["seella", "diff", "cqlsh", "slow.txt"]).is_err());
+    assert!(Cli::try_parse_from([
+        "seella",
+        "diff",
+        "cqlsh",
+        "slow.txt",
+        "--other-path",
+        "fast.txt"
+    ])
+    .is_ok());
+
+    Ok(())
+}
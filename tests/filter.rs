@@ -0,0 +1,85 @@
+mod util;
+
+use regex::Regex;
+use seella::{Cli, EventFilter, Session};
+use util::session;
+
+/// The numbers of the events that were displayed.
+fn displayed(session: &Session, cli: Cli) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session.display(cli, &mut output)?;
+
+    Ok(String::from_utf8(output)?
+        .lines()
+        .skip(9)
+        .map(|line| line.split_whitespace().next().unwrap().parse().unwrap())
+        .collect())
+}
+
+#[test]
+fn keeps_ancestors() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+
+    let by_node = Cli {
+        node: vec!["172.17.0.3".parse()?],
+        ..Default::default()
+    };
+    // The events on the replica are all children of the first event on the coordinator
+    assert_eq!(displayed(&session, by_node)?, [1, 2, 3, 4]);
+
+    let by_thread = EventFilter {
+        threads: vec![String::from("shard 4")],
+        ..Default::default()
+    };
+    assert_eq!(
+        session.filter_events(&by_thread),
+        [true, true, true, true, false, false, false, false, false, false, false, false]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn combines_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+
+    let cli = Cli {
+        activity: Some(Regex::new("(?i)mutation|response")?),
+        min_duration: Some(100),
+        ..Default::default()
+    };
+    assert_eq!(displayed(&session, cli)?, [9, 11]);
+
+    let cli = Cli {
+        max_depth: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(displayed(&session, cli)?, [1, 5, 6, 7, 8, 9, 10, 11, 12]);
+
+    Ok(())
+}
+
+#[test]
+fn waterfall_keeps_its_scale() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+
+    let (mut all, mut filtered) = (Vec::new(), Vec::new());
+    session.display(Cli::default(), &mut all)?;
+    session.display(
+        Cli {
+            min_duration: Some(340),
+            ..Default::default()
+        },
+        &mut filtered,
+    )?;
+
+    let all = String::from_utf8(all)?;
+    let filtered = String::from_utf8(filtered)?;
+    let last_bar = |output: &str| {
+        let line = output.lines().last().unwrap();
+        line[..line.find(']').unwrap()].to_string()
+    };
+    assert_eq!(last_bar(&filtered), last_bar(&all));
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+mod util;
+
+use seella::{session_from_csv, Cli, Session};
+use util::{range_scan, test_data};
+
+fn display(session: &Session, cli: Cli) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session.display(cli, &mut output)?;
+
+    Ok(String::from_utf8(output)?
+        .lines()
+        .skip(9)
+        .map(String::from)
+        .collect())
+}
+
+#[test]
+fn normalised_activity() -> Result<(), Box<dyn std::error::Error>> {
+    let session = range_scan()?;
+    let events = session.events();
+    let activities: Vec<String> = events
+        .iter()
+        .take(5)
+        .map(|(e, _)| e.normalised_activity())
+        .collect();
+
+    assert_eq!(
+        activities,
+        [
+            "Parsing a statement",
+            "read_data: message received from /<ip>",
+            "Creating shard reader on shard: #",
+            "Scanning cache for range (-inf, {#, end}] and slice {(-inf, +inf)}",
+            "Creating shard reader on shard: #",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn groups_runs_of_similar_events() -> Result<(), Box<dyn std::error::Error>> {
+    let session = range_scan()?;
+    let groups = session.group_events();
+
+    let first = &groups[0];
+    assert_eq!(first.index, 4);
+    assert_eq!(first.events.len(), 2);
+    assert_eq!(first.activity(), "Creating shard reader on shard: #");
+
+    let second = &groups[1];
+    assert_eq!(second.index, 6);
+    assert_eq!(second.total_duration(), 214);
+    assert_eq!(second.max_duration(), 117);
+
+    // Every group is a run of siblings on the same node
+    for group in &groups {
+        assert!(group.events.len() >= 2);
+        assert!(group
+            .events
+            .iter()
+            .all(|e| e.durations().0 == e.durations().1));
+        assert!(group
+            .events
+            .iter()
+            .all(|e| e.source == group.events[0].source));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn group_rows() -> Result<(), Box<dyn std::error::Error>> {
+    let session = range_scan()?;
+    let lines = display(
+        &session,
+        Cli {
+            group: true,
+            ..Default::default()
+        },
+    )?;
+    let grouped: usize = session
+        .group_events()
+        .iter()
+        .map(|g| g.events.len() - 1)
+        .sum();
+
+    assert_eq!(lines.len(), session.event_count() - grouped);
+    assert!(lines[4].starts_with("   5 "));
+    assert!(lines[4].contains("Creating shard reader on shard: # (×2, max 0)"));
+    assert!(lines[5].starts_with("   7 "));
+    assert!(lines[5].contains(" 214 "));
+
+    Ok(())
+}
+
+#[test]
+fn expand() -> Result<(), Box<dyn std::error::Error>> {
+    let session = range_scan()?;
+    let lines = display(
+        &session,
+        Cli {
+            group: true,
+            expand: vec![5],
+            ..Default::default()
+        },
+    )?;
+
+    assert!(lines[4].starts_with("   5 "));
+    assert!(lines[4].contains("Creating shard reader on shard: 1 "));
+    assert!(lines[5].starts_with("   6 "));
+    assert!(lines[6].starts_with("   7 "));
+    assert!(lines[6].contains("(×2, max 117)"));
+
+    Ok(())
+}
+
+#[test]
+fn waterfall_unchanged() -> Result<(), Box<dyn std::error::Error>> {
+    // A group covers exactly the same part of the chart as its events together, so those after it don't move
+    let session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+    let range_scan = range_scan()?;
+
+    for session in [session, range_scan] {
+        let plain = display(&session, Cli::default())?;
+        let grouped = display(
+            &session,
+            Cli {
+                group: true,
+                ..Default::default()
+            },
+        )?;
+
+        let last = |lines: &[String]| {
+            let line = lines.last().unwrap();
+            line[..line.find(']').unwrap()].to_string()
+        };
+        assert_eq!(last(&plain), last(&grouped));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+mod util;
+
+use seella::{
+    session_from_csv, session_from_csv_options, Cli, CsvModeOptions, CsvParsingError, EventsPath,
+    SessionsPath,
+};
+use std::path::PathBuf;
+use util::test_data;
+
+const SESSION_ID: &str = "74ff67c0-397b-11ee-8ca4-9688db6cc0f1";
+
+fn display(options: &CsvModeOptions) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session_from_csv_options(options)?
+        .0
+        .display(Cli::default(), &mut output)?;
+    Ok(output)
+}
+
+#[test]
+fn index_is_built_and_reused() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("seella-index-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let events_path = dir.join("events.csv");
+    std::fs::copy(test_data("events.csv"), &events_path)?;
+
+    let mut expected = Vec::new();
+    session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        SESSION_ID,
+    )?
+    .display(Cli::default(), &mut expected)?;
+
+    let options = CsvModeOptions {
+        session_id: String::from(SESSION_ID),
+        sessions_path: SessionsPath(test_data("sessions.csv")),
+        events_path: EventsPath(events_path.clone()),
+        index: true,
+        ..Default::default()
+    };
+
+    // First use builds the index, the second reads it back
+    assert_eq!(display(&options)?, expected);
+    assert!(PathBuf::from(format!("{}.idx", events_path.display())).is_file());
+    assert_eq!(display(&options)?, expected);
+
+    std::fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn index_needs_a_session_id_column() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("seella-index-column-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let events_path = dir.join("events.csv");
+    let events = std::fs::read_to_string(test_data("events.csv"))?;
+    std::fs::write(&events_path, events.replacen("session_id", "sess_id", 1))?;
+
+    let options = CsvModeOptions {
+        session_id: String::from(SESSION_ID),
+        sessions_path: SessionsPath(test_data("sessions.csv")),
+        events_path: EventsPath(events_path.clone()),
+        index: true,
+        ..Default::default()
+    };
+
+    let err = session_from_csv_options(&options).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CsvParsingError>(),
+        Some(CsvParsingError::MissingColumn)
+    ));
+    assert!(!PathBuf::from(format!("{}.idx", events_path.display())).exists());
+
+    std::fs::remove_dir_all(dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn full_scan_matches_early_exit() -> Result<(), Box<dyn std::error::Error>> {
+    let options = CsvModeOptions {
+        session_id: String::from(SESSION_ID),
+        sessions_path: SessionsPath(test_data("sessions.csv")),
+        events_path: EventsPath(test_data("events.csv")),
+        ..Default::default()
+    };
+    let full_scan = CsvModeOptions {
+        full_scan: true,
+        ..options.clone()
+    };
+
+    assert_eq!(display(&options)?, display(&full_scan)?);
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+mod util;
+
+use seella::{session_from_csv, Cli, MaxActivityWidth};
+use util::test_data;
+
+#[test]
+fn fits_to_width() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+
+    for width in [80, 120, 200] {
+        let mut cli = Cli {
+            show_thread: true,
+            ..Default::default()
+        };
+        session.fit_to_width(&mut cli, width);
+
+        let mut output = Vec::new();
+        session.display(cli, &mut output)?;
+        let output = String::from_utf8(output)?;
+
+        // The chart starts after the session details
+        for line in output.lines().skip(8) {
+            assert!(
+                line.chars().count() <= width,
+                "{} > {width}: {line}",
+                line.chars().count()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn truncates_with_ellipsis() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+
+    let display = |auto_width| -> Result<String, Box<dyn std::error::Error>> {
+        let mut output = Vec::new();
+        session.display(
+            Cli {
+                max_activity_width: MaxActivityWidth(20),
+                auto_width,
+                ..Default::default()
+            },
+            &mut output,
+        )?;
+        Ok(String::from_utf8(output)?)
+    };
+
+    let output = display(true)?;
+    assert!(output.contains("├── Creating write hand…\n"));
+    assert!(output.contains("├┬─ Parsing a statement \n"));
+
+    // Without --auto-width, activities are cut short as they always have been
+    let output = display(false)?;
+    assert!(output.contains("├── Creating write handl\n"));
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+mod util;
+
+use seella::{
+    session_from_csv, session_from_csv_options, Cli, CsvFormatOptions, CsvModeOptions,
+    CsvParsingError, CsvSource, Delimiter, EventsPath, SessionsPath,
+};
+use util::test_data;
+use uuid::Uuid;
+
+const SESSION_ID: &str = "74ff67c0-397b-11ee-8ca4-9688db6cc0f1";
+
+#[test]
+fn errors_point_at_the_row() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions_path = test_data("sessions.csv");
+    let events_path = test_data("bad_events.csv");
+    let source = CsvSource::new(&sessions_path, &events_path, Uuid::try_parse(SESSION_ID)?);
+
+    match source.get_data() {
+        Err(err @ CsvParsingError::EventDeserializationErrors(_)) => assert_eq!(
+            err.to_string(),
+            "there were issues deserializing the event data:\n  line 9, column source_elapsed (\"3x2\"): invalid digit found in string"
+        ),
+        other => panic!("expected the bad row to be reported, got {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lenient_skips_bad_rows() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions_path = test_data("sessions.csv");
+    let events_path = test_data("bad_events.csv");
+    let source = CsvSource::new(&sessions_path, &events_path, Uuid::try_parse(SESSION_ID)?);
+
+    let (_, event_records, skipped) = source.get_data_lenient()?;
+    assert_eq!(event_records.len(), 11);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].line, Some(9));
+    assert_eq!(skipped[0].value.as_deref(), Some("3x2"));
+
+    Ok(())
+}
+
+#[test]
+fn unreadable_session_ids_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions_path = test_data("sessions.csv");
+    let events_path = test_data("bad_id_events.csv");
+    let source = CsvSource::new(&sessions_path, &events_path, Uuid::try_parse(SESSION_ID)?);
+
+    let (_, event_records, skipped) = source.get_data_lenient()?;
+    assert_eq!(event_records.len(), 10);
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0].line, Some(4));
+    assert_eq!(
+        skipped[0].to_string(),
+        "line 4: UUID parsing failed: invalid group count: expected 5, found 4"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn alternative_copy_to_options() -> Result<(), Box<dyn std::error::Error>> {
+    let options = CsvModeOptions {
+        session_id: String::from(SESSION_ID),
+        sessions_path: SessionsPath(test_data("pipe_sessions.csv")),
+        events_path: EventsPath(test_data("pipe_events.csv")),
+        format: CsvFormatOptions {
+            delimiter: Delimiter(b'|'),
+            no_header: true,
+            null: Some(String::from("null")),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (session, _) = session_from_csv_options(&options)?;
+    let expected = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        SESSION_ID,
+    )?;
+
+    assert_eq!(session.username, None);
+    assert_eq!(session.event_count(), expected.event_count());
+
+    let (mut output, mut expected_output) = (Vec::new(), Vec::new());
+    session.display(Cli::default(), &mut output)?;
+    expected.display(Cli::default(), &mut expected_output)?;
+    let output = String::from_utf8(output)?;
+    let expected_output = String::from_utf8(expected_output)?;
+
+    // Only the username differs, as it was exported as null
+    assert_eq!(output.replacen("(N/A)", "(anonymous)", 1), expected_output);
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+mod util;
+
+use seella::{session_from_csv, Cli, LintKind, MaxGap, OutputFormat, Session};
+use util::{range_scan, test_data};
+
+fn session() -> Result<Session, Box<dyn std::error::Error>> {
+    session_from_csv(
+        &test_data("lint_sessions.csv"),
+        &test_data("lint_events.csv"),
+        "7c3e5a40-397b-11ee-8ca4-9688db6cc0f1",
+    )
+}
+
+#[test]
+fn findings() -> Result<(), Box<dyn std::error::Error>> {
+    let findings: Vec<(usize, LintKind)> = session()?
+        .lint(&Cli::default())
+        .into_iter()
+        .map(|finding| (finding.row, finding.kind))
+        .collect();
+
+    assert_eq!(
+        findings,
+        [
+            (4, LintKind::ElapsedBackwards),
+            (9, LintKind::DeadReplica),
+            (10, LintKind::Throttling),
+            (11, LintKind::Gap),
+            (12, LintKind::CrossDc),
+            (14, LintKind::Gap),
+            (14, LintKind::SpeculativeRetry),
+            (15, LintKind::Gap),
+            (15, LintKind::Timeout),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn max_gap() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli {
+        max_gap: MaxGap(5000),
+        ..Default::default()
+    };
+    let gaps: Vec<usize> = session()?
+        .lint(&cli)
+        .into_iter()
+        .filter(|finding| finding.kind == LintKind::Gap)
+        .map(|finding| finding.row)
+        .collect();
+
+    assert_eq!(gaps, [14]);
+
+    Ok(())
+}
+
+#[test]
+fn grouped_rows() -> Result<(), Box<dyn std::error::Error>> {
+    let session = range_scan()?;
+    let cli = Cli {
+        group: true,
+        lint: true,
+        max_gap: MaxGap(1),
+        ..Default::default()
+    };
+    let mut output = Vec::new();
+    session.display(cli.clone(), &mut output)?;
+    let output = String::from_utf8(output)?;
+    let (chart, _) = output.split_once("\n\n row").unwrap();
+    let shown: Vec<usize> = chart
+        .lines()
+        .skip(9)
+        .map(|line| line.split_whitespace().next().unwrap().parse())
+        .collect::<Result<_, _>>()?;
+
+    // Events within a group are flagged against the group's row, which is the only one shown
+    let grouped = session.lint(&cli);
+    let ungrouped = session.lint(&Cli {
+        max_gap: MaxGap(1),
+        ..Default::default()
+    });
+    assert_eq!(grouped.len(), ungrouped.len());
+    assert_ne!(grouped, ungrouped);
+    for finding in &grouped {
+        assert!(shown.contains(&finding.row));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn nothing_to_flag() -> Result<(), Box<dyn std::error::Error>> {
+    // Throttling that didn't delay anything isn't worth flagging
+    let session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+
+    assert!(session.lint(&Cli::default()).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn text() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session()?.display(
+        Cli {
+            lint: true,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let output = String::from_utf8(output)?;
+    let lint = output.split_once("required responses").unwrap().1;
+    assert_eq!(
+        lint.split_once('\n').unwrap().1,
+        r#"
+row kind              finding
+  4 elapsed backwards went back 3 since the previous event on this span
+  9 dead replica      172.17.0.4 down for this write
+ 10 throttling        Delay decision due to throttling: delay, throttling for 2000us
+ 11 gap               waited 2015 since the previous event on this span
+ 12 cross dc          Sending a mutation to /172.18.0.5 to be forwarded to the remote dc
+ 14 gap               waited 10010 since the previous event on this span
+ 14 speculative retry Speculating write to /172.18.0.5 after no response
+ 15 gap               waited 3000 since the previous event on this span
+ 15 timeout           Write timeout; received 1 of 2 required responses
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session()?.display(
+        Cli {
+            lint: true,
+            format: OutputFormat::Json,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(
+        json["lint"][1],
+        serde_json::json!({
+            "row": 9,
+            "kind": "dead_replica",
+            "message": "172.17.0.4 down for this write",
+        })
+    );
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+mod util;
+
+use seella::{session_from_csv, Cli, PaxosPhase, Session, WaterfallWidth};
+use util::test_data;
+
+fn session() -> Result<Session, Box<dyn std::error::Error>> {
+    session_from_csv(
+        &test_data("paxos_sessions.csv"),
+        &test_data("paxos_events.csv"),
+        "7b2d4f30-397b-11ee-8ca4-9688db6cc0f1",
+    )
+}
+
+#[test]
+fn phase_of_activity() {
+    let phases: Vec<Option<PaxosPhase>> = [
+        "Preparing 7b2d4f31-397b-11ee-0000-000000000001",
+        "Promising ballot 7b2d4f31-397b-11ee-0000-000000000001",
+        "Reading existing values for CAS precondition",
+        "CAS precondition is met; proposing client-requested updates",
+        "accept_proposal: sending accept proposal to /172.17.0.3",
+        "Accepting proposal Commit(7b2d4f31-397b-11ee-0000-000000000001)",
+        "Committing proposal Commit(7b2d4f31-397b-11ee-0000-000000000001)",
+        "paxos_learn: message received from /172.17.0.2",
+        "prune: sending prune to /172.17.0.3",
+        "Done processing - preparing a result",
+        "Appending to commitlog",
+    ]
+    .iter()
+    .map(|activity| PaxosPhase::of(activity))
+    .collect();
+
+    assert_eq!(
+        phases,
+        [
+            Some(PaxosPhase::Prepare),
+            Some(PaxosPhase::Prepare),
+            Some(PaxosPhase::Read),
+            Some(PaxosPhase::Read),
+            Some(PaxosPhase::Accept),
+            Some(PaxosPhase::Accept),
+            Some(PaxosPhase::Learn),
+            Some(PaxosPhase::Learn),
+            Some(PaxosPhase::Prune),
+            None,
+            None,
+        ]
+    );
+}
+
+#[test]
+fn phases() -> Result<(), Box<dyn std::error::Error>> {
+    let report = session()?.paxos_report();
+
+    let phases: Vec<(PaxosPhase, usize, usize)> = report
+        .phases
+        .iter()
+        .map(|timing| (timing.phase, timing.events, timing.nodes.len()))
+        .collect();
+    assert_eq!(
+        phases,
+        [
+            (PaxosPhase::Prepare, 18, 3),
+            (PaxosPhase::Read, 1, 1),
+            (PaxosPhase::Accept, 8, 3),
+            (PaxosPhase::Learn, 7, 3),
+            (PaxosPhase::Prune, 4, 3),
+        ]
+    );
+
+    // Timed by the coordinator, the phases follow on from one another
+    for pair in report.phases.windows(2) {
+        assert_eq!(pair[0].end, pair[1].start);
+    }
+
+    assert_eq!(report.rounds, 2);
+    assert_eq!(report.contention.len(), 2);
+    assert!(report.contention[0].starts_with("172.17.0.4: Promise rejected"));
+
+    Ok(())
+}
+
+#[test]
+fn not_a_transaction() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+    let report = session.paxos_report();
+
+    assert!(report.phases.is_empty());
+    assert_eq!(report.rounds, 0);
+    assert!(report.contention.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn timeline() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session()?.display(
+        Cli {
+            paxos: true,
+            waterfall_width: WaterfallWidth(40),
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let output = String::from_utf8(output)?;
+    let report = output.split_once("preparing a result").unwrap().1;
+    assert_eq!(
+        report.split_once('\n').unwrap().1,
+        r#"
+phase   waterfall chart                            events nodes      start        end       time
+prepare [           ███████                      ]     18     3       3496       5659       2679
+read    [                  █                     ]      1     1       5659       6089        430
+accept  [                   ███████              ]      8     3       6089       8191       2977
+learn   [                          ██████        ]      7     3       8191      10086       3175
+prune   [                                █████   ]      4     3      10086      11717       2441
+warning: took 2 rounds to prepare a ballot
+contention: 172.17.0.4: Promise rejected; ballot 7b2d4f31-397b-11ee-0000-000000000001 is older than the one already promised
+contention: 172.17.0.2: prepare_ballot: promise rejected by /172.17.0.4, retrying with a higher ballot
+"#
+    );
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+mod util;
+
+use seella::{display_profiles, profiles, Cli, OutputFormat, Profile, WaterfallWidth};
+use util::many_sessions;
+
+fn profiles_of_many() -> Result<Vec<Profile>, Box<dyn std::error::Error>> {
+    Ok(profiles(&many_sessions()?))
+}
+
+#[test]
+fn merged_tree() -> Result<(), Box<dyn std::error::Error>> {
+    let profiles = profiles_of_many()?;
+    let insert = &profiles[0];
+    assert_eq!(insert.sessions, 4);
+
+    let nodes: Vec<(&str, usize, usize, i64, i64)> = insert
+        .nodes()
+        .into_iter()
+        .map(|(node, depth)| {
+            (
+                node.activity.as_str(),
+                depth,
+                node.count,
+                node.mean,
+                node.p99,
+            )
+        })
+        .collect();
+    assert_eq!(
+        nodes[..5],
+        [
+            ("Parsing a statement", 0, 4, 0, 0),
+            ("Message received from /<ip>", 1, 4, 7, 17),
+            // Only in one of the sessions, but still in its place
+            ("Waiting for a free slot in the commitlog", 1, 1, 12, 12),
+            ("Sending mutation_done to /<ip>", 1, 4, 174, 405),
+            ("Mutation handling is done", 1, 4, 179, 409),
+        ]
+    );
+    assert_eq!(nodes.len(), 13);
+    assert_eq!(
+        nodes[10],
+        (
+            "Delay decision due to throttling: do not delay, resuming now",
+            0,
+            3,
+            588,
+            1165
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn text() -> Result<(), Box<dyn std::error::Error>> {
+    let profiles = profiles_of_many()?;
+    let mut output = Vec::new();
+    display_profiles(
+        &Cli {
+            waterfall_width: WaterfallWidth(20),
+            ..Default::default()
+        },
+        &profiles[1..2],
+        &mut output,
+    )?;
+
+    assert_eq!(
+        String::from_utf8(output)?,
+        r#"Query: CREATE KEYSPACE k WITH REPLICATION = { ? : ?, ? : ? }; (1 sessions)
+
+  waterfall chart            mean      p99  seen    activity
+1 [█                   ]        0        0  100% ├─ Parsing a statement
+2 [█                   ]       68       68  100% ├─ Processing a statement
+3 [████████████████████]    12268    12268  100% ├─ Done processing - preparing a result
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json() -> Result<(), Box<dyn std::error::Error>> {
+    let profiles = profiles_of_many()?;
+    let mut output = Vec::new();
+    display_profiles(
+        &Cli {
+            format: OutputFormat::Json,
+            ..Default::default()
+        },
+        &profiles,
+        &mut output,
+    )?;
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json.as_array().unwrap().len(), 3);
+    assert_eq!(
+        json[0]["roots"][0]["children"][1],
+        serde_json::json!({
+            "activity": "Waiting for a free slot in the commitlog",
+            "on_coordinator": false,
+            "count": 1,
+            "mean": 12,
+            "p99": 12,
+            "children": [],
+        })
+    );
+
+    Ok(())
+}
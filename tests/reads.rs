@@ -0,0 +1,128 @@
+mod util;
+
+use seella::{session_from_csv, Cli, MaxSstables, NodeReads, Session};
+use util::test_data;
+
+fn session() -> Result<Session, Box<dyn std::error::Error>> {
+    session_from_csv(
+        &test_data("read_sessions.csv"),
+        &test_data("read_events.csv"),
+        "7a1c3e20-397b-11ee-8ca4-9688db6cc0f1",
+    )
+}
+
+#[test]
+fn per_replica() -> Result<(), Box<dyn std::error::Error>> {
+    let diagnostics = session()?.read_diagnostics(&Cli::default());
+
+    assert_eq!(
+        diagnostics.nodes,
+        [
+            NodeReads {
+                source: "172.17.0.2".parse()?,
+                partitions: 0,
+                live_rows: 0,
+                tombstones: 0,
+                sstables: 0,
+                cache_reads: 0,
+                cache_misses: 0,
+                digest_mismatches: 1,
+                read_repairs: 1,
+            },
+            NodeReads {
+                source: "172.17.0.3".parse()?,
+                partitions: 1,
+                live_rows: 2,
+                tombstones: 11,
+                sstables: 2,
+                cache_reads: 1,
+                cache_misses: 1,
+                digest_mismatches: 0,
+                read_repairs: 0,
+            },
+            NodeReads {
+                source: "172.17.0.4".parse()?,
+                partitions: 0,
+                live_rows: 0,
+                tombstones: 0,
+                sstables: 0,
+                cache_reads: 1,
+                cache_misses: 0,
+                digest_mismatches: 0,
+                read_repairs: 0,
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn warnings() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+
+    assert_eq!(
+        session.read_diagnostics(&Cli::default()).warnings,
+        ["172.17.0.3 read 11 tombstones for 2 live rows (85%)"]
+    );
+
+    let strict = Cli {
+        max_sstables: MaxSstables(1),
+        ..Default::default()
+    };
+    assert_eq!(
+        session.read_diagnostics(&strict).warnings,
+        [
+            "172.17.0.3 read 11 tombstones for 2 live rows (85%)",
+            "172.17.0.3 read from 2 sstables"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn text() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session()?.display(
+        Cli {
+            reads: true,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let output = String::from_utf8(output)?;
+    let report = output.split_once("preparing a result").unwrap().1;
+    assert_eq!(
+        report.split_once('\n').unwrap().1,
+        r#"
+node            partitions  live rows tombstones sstables cache reads cache misses digest mismatches read repairs
+172.17.0.2               0          0          0        0           0            0                 1            1
+172.17.0.3               1          2         11        2           1            1                 0            0
+172.17.0.4               0          0          0        0           1            0                 0            0
+warning: 172.17.0.3 read 11 tombstones for 2 live rows (85%)
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn range_scan() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session_from_csv(
+        &test_data("range_sessions.csv"),
+        &test_data("events.csv"),
+        "75007930-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+    let diagnostics = session.read_diagnostics(&Cli::default());
+
+    // Every page was read from the cache on the replica
+    let replica = &diagnostics.nodes[1];
+    assert_eq!(replica.source, "172.17.0.3".parse::<std::net::IpAddr>()?);
+    assert_eq!(replica.live_rows, 3);
+    assert_eq!(replica.cache_misses, 0);
+    assert!(diagnostics.warnings.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod util;
+
+use seella::{redact_cql, session_from_csv, Cli, CsvSource, Redactor};
+use util::test_data;
+use uuid::Uuid;
+
+#[test]
+fn redacted_display() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    let mut session = session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+    session.redact(&mut Redactor::new());
+    session.display(Cli::default(), &mut output)?;
+
+    assert_eq!(
+        output,
+        r#"Session ID: 74ff67c0-397b-11ee-8ca4-9688db6cc0f1
+2023-08-13T01:48:10.172+00:00
+192.0.2.1       (redacted) -> 192.0.2.1      
+Request Size:  84
+Response Size: 20
+Execute CQL3 query
+"{'consistency_level': 'ONE', 'page_size': '100', 'query': 'INSERT INTO k.t (pk, t, v, s) VALUES (?, ?, ?, ?);', 'serial_consistency_level': 'SERIAL', 'user_timestamp': '1691891290172041'}"
+
+   waterfall chart                                                                                        dur    node                activity                                                            
+ 1 [█─────┤                                                                                             ] 0      192.0.2.1       ├┬─ Parsing a statement                                                 
+ 2 [█                                                                                                   ] 5      192.0.2.2       │├─ Message received from /192.0.2.1                                    
+ 3 [███                                                                                                 ] 55     192.0.2.2       │├─ Sending mutation_done to /192.0.2.1                                 
+ 4 [   ████                                                                                             ] 58     192.0.2.2       │├─ Mutation handling is done                                           
+ 5 [       █                                                                                            ] 27     192.0.2.1       ├── Processing a statement                                              
+ 6 [        ███                                                                                         ] 45     192.0.2.1       ├── Creating write handler for token: ? natural: {192.0.2.2} pending: {}
+ 7 [           ███                                                                                      ] 46     192.0.2.1       ├── Creating write handler with live: {192.0.2.2} dead: {}              
+ 8 [              ███                                                                                   ] 49     192.0.2.1       ├── Sending a mutation to /192.0.2.2                                    
+ 9 [                 ████████████████████                                                               ] 332    192.0.2.1       ├── Got a response from /192.0.2.2                                      
+10 [                                     █████████████████████                                          ] 333    192.0.2.1       ├── Delay decision due to throttling: do not delay, resuming now        
+11 [                                                          ████████████████████                      ] 339    192.0.2.1       ├── Mutation successfully completed                                     
+12 [                                                                              ██████████████████████] 344    192.0.2.1       ├── Done processing - preparing a result                                
+"#.as_bytes()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn redacted_records_match_session() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut session_record, mut event_records) = CsvSource::new(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        Uuid::try_parse("74ff67c0-397b-11ee-8ca4-9688db6cc0f1")?,
+    )
+    .get_data()?;
+    Redactor::new().redact_records(&mut session_record, &mut event_records);
+
+    assert_eq!(session_record.coordinator.to_string(), "192.0.2.1");
+    assert_eq!(session_record.username.as_deref(), Some("redacted"));
+    assert!(event_records
+        .iter()
+        .all(|event| !event.activity.contains("172.17.0")));
+
+    Ok(())
+}
+
+#[test]
+fn literals() {
+    assert_eq!(
+        redact_cql("SELECT * FROM ks.t1 WHERE pk = 'it''s' AND ck > -5 AND id = 74ff67c0-397b-11ee-8ca4-9688db6cc0f1"),
+        "SELECT * FROM ks.t1 WHERE pk = ? AND ck > ? AND id = ?"
+    );
+    assert_eq!(
+        redact_cql(r#"UPDATE "Users" SET b = 0xcafe, f = 1.5 WHERE k = $$secret$$"#),
+        r#"UPDATE "Users" SET b = ?, f = ? WHERE k = ?"#
+    );
+
+    let redactor = Redactor::new();
+    assert_eq!(
+        redactor.parameters(r#"{"query": "SELECT * FROM t WHERE k = 'x'", "bound_var_0_k": "'x'", "page_size": "100"}"#),
+        r#"{"query": "SELECT * FROM t WHERE k = ?", "bound_var_0_k": "?", "page_size": "100"}"#
+    );
+}
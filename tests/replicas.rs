@@ -0,0 +1,105 @@
+mod util;
+
+use seella::{session_from_csv, Cli, OutputFormat, ReplicaReport, ReplicaRequest, WriteHandler};
+use util::{session, test_data};
+
+#[test]
+fn write() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+    let replica = "172.17.0.3".parse()?;
+
+    assert_eq!(
+        session.replica_report(),
+        ReplicaReport {
+            write_handlers: vec![WriteHandler {
+                token: Some(-3485513579396041028),
+                natural: vec![replica],
+                pending: vec![],
+                live: vec![replica],
+                dead: vec![],
+            }],
+            requests: vec![ReplicaRequest {
+                replica,
+                verb: String::from("mutation"),
+                sent_at: Some(236),
+                acked_at: Some(285),
+                round_trip: Some(49),
+            }],
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn range_scan() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session_from_csv(
+        &test_data("range_sessions.csv"),
+        &test_data("events.csv"),
+        "75007930-397b-11ee-8ca4-9688db6cc0f1",
+    )?;
+    let report = session.replica_report();
+
+    assert!(report.write_handlers.is_empty());
+    assert_eq!(report.requests.len(), 135);
+    assert!(report
+        .requests
+        .iter()
+        .all(|r| r.verb == "read_data" && r.round_trip.is_some_and(|rtt| rtt >= 0)));
+
+    Ok(())
+}
+
+#[test]
+fn text() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session()?.display(
+        Cli {
+            replicas: true,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let output = String::from_utf8(output)?;
+    let report = output.split_once("preparing a result").unwrap().1;
+    assert_eq!(
+        report.split_once('\n').unwrap().1,
+        r#"
+token                natural         pending         live            dead
+-3485513579396041028 172.17.0.3                      172.17.0.3
+
+replica         request                 sent at   acked at round trip
+172.17.0.3      mutation                    236        285         49
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    session()?.display(
+        Cli {
+            replicas: true,
+            format: OutputFormat::Json,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json["session_id"], "74ff67c0-397b-11ee-8ca4-9688db6cc0f1");
+    assert_eq!(json["duration"], 1633);
+    assert_eq!(
+        json["replicas"]["write_handlers"][0]["dead"],
+        serde_json::json!([])
+    );
+    assert_eq!(json["replicas"]["requests"][0]["replica"], "172.17.0.3");
+    assert_eq!(json["replicas"]["requests"][0]["round_trip"], 49);
+    // Only the reports that were asked for
+    assert!(json.get("summary").is_none());
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+mod util;
+
+use seella::{
+    display_slow_log, find_slow_log_entry, session_from_csv_options, slow_log_from_csv_options,
+    Cli, CsvSlowLogOptions, OperationMode, SlowLogPath, SlowLogRecord, SlowLogSource,
+};
+use util::{many_sessions_options, test_data};
+
+fn slow_log_options() -> CsvSlowLogOptions {
+    let many = many_sessions_options();
+    CsvSlowLogOptions {
+        slow_log_path: SlowLogPath(test_data("slow_log.csv")),
+        sessions_path: many.sessions_path,
+        events_path: many.events_path,
+        ..Default::default()
+    }
+}
+
+fn slow_log() -> Result<Vec<SlowLogRecord>, Box<dyn std::error::Error>> {
+    slow_log_from_csv_options(&slow_log_options())
+}
+
+#[test]
+fn oldest_first() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = slow_log()?;
+    let entries: Vec<(String, i32, Vec<String>)> = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.session_id.to_string()[..8].to_string(),
+                entry.duration,
+                entry.tables(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (String::from("74612bf0"), 10764, vec![]),
+            (String::from("7d4f6b50"), 712, vec![String::from("k.t")]),
+            (String::from("7f6b9d70"), 1210, vec![String::from("k.t")]),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn find_entry() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = slow_log()?;
+    let session_id = |entry: &str| {
+        find_slow_log_entry(&entries, entry).map(|entry| entry.session_id.to_string())
+    };
+
+    let expected = Some(String::from("7d4f6b50-397b-11ee-8ca4-9688db6cc0f1"));
+    assert_eq!(session_id("2"), expected);
+    assert_eq!(session_id("7d4f6b50-397b-11ee-8ca4-9688db6cc0f1"), expected);
+    assert_eq!(session_id("7d4fe020-397b-11ee-8ca4-9688db6cc0f1"), expected);
+    assert_eq!(session_id("0"), None);
+    assert_eq!(session_id("4"), None);
+    Ok(())
+}
+
+#[test]
+fn show_entry() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = slow_log()?;
+    let entry = find_slow_log_entry(&entries, "3").unwrap();
+
+    let source = SlowLogSource::Csv(slow_log_options());
+    let OperationMode::Csv(options) = source.with_session(&entry.session_id.to_string()) else {
+        panic!("expected the csv mode of operation");
+    };
+    let (session, _) = session_from_csv_options(&options)?;
+    assert_eq!(session.id, entry.session_id);
+    assert_eq!(session.event_count(), 12);
+    Ok(())
+}
+
+#[test]
+fn list() -> Result<(), Box<dyn std::error::Error>> {
+    let mut output = Vec::new();
+    display_slow_log(&Cli::default(), &slow_log()?, &mut output)?;
+
+    let expected = "\
+# date                            duration node            client          session id                           tables          query
+1 2023-08-13T01:48:09.135+00:00      10764 172.17.0.2      172.17.0.1      74612bf0-397b-11ee-8ca4-9688db6cc0f1                 CREATE TABLE k.t ( pk int, t int, v text, s text static, PRIMARY KEY (pk, t) );
+2 2023-08-13T01:48:12.480+00:00        712 172.17.0.2      172.17.0.1      7d4f6b50-397b-11ee-8ca4-9688db6cc0f1 k.t             INSERT INTO k.t (pk, t, v, s) VALUES (?, ?, ?, ?);
+3 2023-08-13T01:48:15.613+00:00       1210 172.17.0.2      172.17.0.1      7f6b9d70-397b-11ee-8ca4-9688db6cc0f1 k.t             INSERT INTO k.t (pk, t, v, s) VALUES (?, ?, ?, ?);
+";
+    assert_eq!(String::from_utf8(output)?, expected);
+    Ok(())
+}
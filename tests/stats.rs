@@ -0,0 +1,149 @@
+mod util;
+
+use seella::{display_stats, query_stats, Cli, OutputFormat, Percentiles, Timestamp};
+use util::many_sessions;
+
+#[test]
+fn every_session() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = many_sessions()?;
+
+    // Events without a session, such as those of 74fe2f40, are left out
+    let counts: Vec<usize> = sessions.iter().map(|s| s.event_count()).collect();
+    assert_eq!(counts, [3, 3, 12, 12, 12, 12]);
+
+    Ok(())
+}
+
+#[test]
+fn grouped_by_query() -> Result<(), Box<dyn std::error::Error>> {
+    let stats = query_stats(&many_sessions()?);
+
+    let queries: Vec<(&str, usize)> = stats
+        .iter()
+        .map(|query| (query.query.as_str(), query.sessions))
+        .collect();
+    assert_eq!(
+        queries,
+        [
+            ("INSERT INTO k.t (pk, t, v, s) VALUES (?, ?, ?, ?);", 4),
+            ("CREATE KEYSPACE k WITH REPLICATION = { ? : ?, ? : ? };", 1),
+            (
+                "CREATE TABLE k.t ( pk int, t int, v text, s text static, PRIMARY KEY (pk, t) );",
+                1
+            ),
+        ]
+    );
+    assert_eq!(
+        stats[0].duration,
+        Percentiles {
+            p50: 346,
+            p90: 1210,
+            p99: 1210,
+            max: 1210,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn activities() -> Result<(), Box<dyn std::error::Error>> {
+    let stats = query_stats(&many_sessions()?);
+    let insert = &stats[0];
+
+    let throttling = insert
+        .activities
+        .iter()
+        .find(|activity| activity.activity.starts_with("Delay decision"))
+        .unwrap();
+    // One of the sessions didn't wait on throttling at all
+    assert_eq!(throttling.sessions, 3);
+    assert_eq!(throttling.duration.p50, 333);
+
+    let commitlog = insert.activities.last().unwrap();
+    assert_eq!(
+        commitlog.activity,
+        "Waiting for a free slot in the commitlog"
+    );
+    assert_eq!(commitlog.sessions, 1);
+
+    Ok(())
+}
+
+#[test]
+fn percentiles() {
+    assert_eq!(Percentiles::new(Vec::new()), Percentiles::default());
+    assert_eq!(
+        Percentiles::new((1..=200).rev().collect()),
+        Percentiles {
+            p50: 100,
+            p90: 180,
+            p99: 198,
+            max: 200,
+        }
+    );
+}
+
+#[test]
+fn text() -> Result<(), Box<dyn std::error::Error>> {
+    let stats = query_stats(&many_sessions()?);
+    let mut output = Vec::new();
+    display_stats(&Cli::default(), &stats[1..2], &mut output)?;
+
+    assert_eq!(
+        String::from_utf8(output)?,
+        r#"Query: CREATE KEYSPACE k WITH REPLICATION = { ? : ?, ? : ? };
+sessions        p50        p90        p99        max activity
+       1      12275      12275      12275      12275 (session duration)
+       1          0          0          0          0 Parsing a statement
+       1         68         68         68         68 Processing a statement
+       1      12268      12268      12268      12268 Done processing - preparing a result
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json() -> Result<(), Box<dyn std::error::Error>> {
+    let stats = query_stats(&many_sessions()?);
+    let mut output = Vec::new();
+    display_stats(
+        &Cli {
+            format: OutputFormat::Json,
+            ..Default::default()
+        },
+        &stats,
+        &mut output,
+    )?;
+
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json[0]["sessions"], 4);
+    assert_eq!(
+        json[0]["activities"][1],
+        serde_json::json!({
+            "activity": "Message received from /<ip>",
+            "sessions": 4,
+            "duration": { "p50": 5, "p90": 17, "p99": 17, "max": 17 },
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn timestamps() -> Result<(), Box<dyn std::error::Error>> {
+    let at: Timestamp = "2023-08-13T01:48:00Z".parse()?;
+    assert_eq!(at.to_rfc3339(), "2023-08-13T01:48:00+00:00");
+
+    let ago: Timestamp = "2h".parse()?;
+    let minutes = (chrono::Utc::now() - *ago).num_minutes();
+    assert!((119..=120).contains(&minutes));
+
+    assert!("yesterday".parse::<Timestamp>().is_err());
+    assert!("-5m".parse::<Timestamp>().is_err());
+    assert!("99999999999999d".parse::<Timestamp>().is_err());
+    assert!("999999999999999999s".parse::<Timestamp>().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+mod util;
+
+use seella::{Breakdown, Cli};
+use util::session;
+
+#[test]
+fn per_node() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+
+    assert_eq!(
+        session.breakdown(false),
+        [
+            Breakdown {
+                source: "172.17.0.2".parse()?,
+                thread: None,
+                events: 9,
+                self_time: 1515,
+                first_offset: 0,
+                last_offset: 1289,
+            },
+            Breakdown {
+                source: "172.17.0.3".parse()?,
+                thread: None,
+                events: 3,
+                self_time: 118,
+                first_offset: 0,
+                last_offset: 60,
+            },
+        ]
+    );
+
+    // Every event is accounted for
+    let breakdowns = session.breakdown(true);
+    assert_eq!(
+        breakdowns.iter().map(|b| b.events).sum::<usize>(),
+        session.event_count()
+    );
+    assert_eq!(
+        breakdowns.iter().map(|b| b.self_time).sum::<i64>(),
+        session.total_duration()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn summary_tables() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+    let mut output = Vec::new();
+    session.display(
+        Cli {
+            summary: true,
+            ..Default::default()
+        },
+        &mut output,
+    )?;
+
+    let output = String::from_utf8(output)?;
+    let summary = output.split_once("preparing a result").unwrap().1;
+    assert_eq!(
+        summary.split_once('\n').unwrap().1,
+        r#"
+node            events  self time      first       last self %
+172.17.0.2           9       1515          0       1289  92.8%
+172.17.0.3           3        118          0         60   7.2%
+
+node            shard   events  self time      first       last self %
+172.17.0.2      shard 0      9       1515          0       1289  92.8%
+172.17.0.3      shard 4      3        118          0         60   7.2%
+"#
+    );
+
+    Ok(())
+}
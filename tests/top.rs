@@ -0,0 +1,110 @@
+mod util;
+
+use regex::Regex;
+use seella::{display_top, top, Cli, Session, SessionFilter};
+use util::many_sessions;
+
+fn ids(sessions: &[Session]) -> Vec<String> {
+    sessions
+        .iter()
+        .map(|session| session.id.to_string()[..8].to_string())
+        .collect()
+}
+
+#[test]
+fn slowest_first() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = top(many_sessions()?, &SessionFilter::default(), 4);
+    assert_eq!(
+        ids(&sessions),
+        ["74207970", "74612bf0", "7f6b9d70", "7d4f6b50"]
+    );
+    Ok(())
+}
+
+#[test]
+fn keyspace_and_table() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = many_sessions()?;
+    let names: Vec<_> = sessions[..3]
+        .iter()
+        .map(|session| session.keyspace_and_table())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            (Some(String::from("k")), None),
+            (Some(String::from("k")), Some(String::from("t"))),
+            (Some(String::from("k")), Some(String::from("t"))),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn filtered_by_table() -> Result<(), Box<dyn std::error::Error>> {
+    let filter = SessionFilter {
+        table: Some(String::from("T")),
+        ..Default::default()
+    };
+    let sessions = top(many_sessions()?, &filter, 10);
+    assert_eq!(
+        ids(&sessions),
+        ["74612bf0", "7f6b9d70", "7d4f6b50", "74ff67c0", "7e5a8c60"]
+    );
+    Ok(())
+}
+
+#[test]
+fn filtered_by_coordinator() -> Result<(), Box<dyn std::error::Error>> {
+    let filter = SessionFilter {
+        coordinators: vec!["172.17.0.3".parse()?],
+        ..Default::default()
+    };
+    assert!(top(many_sessions()?, &filter, 10).is_empty());
+
+    let filter = SessionFilter {
+        coordinators: vec!["172.17.0.2".parse()?],
+        command: Some(String::from("query")),
+        ..Default::default()
+    };
+    assert_eq!(top(many_sessions()?, &filter, 10).len(), 6);
+    Ok(())
+}
+
+#[test]
+fn leaderboard() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = top(many_sessions()?, &SessionFilter::default(), 2);
+    let mut output = Vec::new();
+    display_top(&Cli::default(), &sessions, true, None, &mut output)?;
+
+    let expected = "\
+# session id                           started at                      duration coordinator     query
+1 74207970-397b-11ee-8ca4-9688db6cc0f1 2023-08-13T01:48:08.711+00:00      12275 172.17.0.2      CREATE KEYSPACE k WITH REPLICATION = { ? : ?, ? : ? };
+2 74612bf0-397b-11ee-8ca4-9688db6cc0f1 2023-08-13T01:48:09.135+00:00      10764 172.17.0.2      CREATE TABLE k.t ( pk int, t int, v text, s text static, PRIMARY KEY (pk, t) );
+";
+    assert_eq!(String::from_utf8(output)?, expected);
+    Ok(())
+}
+
+#[test]
+fn shown_in_full() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = top(many_sessions()?, &SessionFilter::default(), 2);
+    let mut output = Vec::new();
+    display_top(&Cli::default(), &sessions, false, None, &mut output)?;
+
+    let output = String::from_utf8(output)?;
+    assert_eq!(output.matches("Session ID: ").count(), 2);
+    assert!(output.find("Session ID: 74207970") < output.find("Session ID: 74612bf0"));
+    Ok(())
+}
+
+#[test]
+fn filtered_by_duration_and_query() -> Result<(), Box<dyn std::error::Error>> {
+    let filter = SessionFilter {
+        min_duration: Some(700),
+        query: Some(Regex::new("^INSERT")?),
+        ..Default::default()
+    };
+    let sessions = top(many_sessions()?, &filter, 10);
+    assert_eq!(ids(&sessions), ["7f6b9d70", "7d4f6b50"]);
+    Ok(())
+}
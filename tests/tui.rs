@@ -0,0 +1,94 @@
+mod util;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use seella::{Browser, DurationFormat};
+use util::session;
+
+fn press(browser: &mut Browser, keys: &str) {
+    for c in keys.chars() {
+        let code = match c {
+            '\n' => KeyCode::Enter,
+            '\t' => KeyCode::Tab,
+            c => KeyCode::Char(c),
+        };
+        assert!(!browser.handle_key(KeyEvent::from(code)));
+    }
+}
+
+fn selected_activity<'a>(browser: &Browser<'a>) -> &'a str {
+    &browser.selected_event().unwrap().activity
+}
+
+#[test]
+fn collapse_and_expand() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+    let mut browser = Browser::new(&session, DurationFormat::default());
+    assert_eq!(browser.visible_events().len(), 12);
+
+    // The first event has the three events from the replica as children
+    press(&mut browser, "\n");
+    assert_eq!(browser.visible_events().len(), 9);
+    press(&mut browser, "j");
+    assert_eq!(selected_activity(&browser), "Processing a statement");
+
+    press(&mut browser, "k\n");
+    assert_eq!(browser.visible_events().len(), 12);
+
+    // Collapsing a leaf moves up to its parent instead
+    press(&mut browser, "jh");
+    assert_eq!(selected_activity(&browser), "Parsing a statement");
+    assert_eq!(browser.visible_events().len(), 12);
+
+    Ok(())
+}
+
+#[test]
+fn search_and_jump_between_nodes() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+    let mut browser = Browser::new(&session, DurationFormat::default());
+
+    // Searching reveals matches within collapsed events
+    press(&mut browser, "\n/MUTATION\n");
+    assert_eq!(
+        selected_activity(&browser),
+        "Sending mutation_done to /172.17.0.2"
+    );
+    assert_eq!(browser.visible_events().len(), 12);
+
+    press(&mut browser, "n");
+    assert_eq!(selected_activity(&browser), "Mutation handling is done");
+    press(&mut browser, "n");
+    assert_eq!(
+        selected_activity(&browser),
+        "Sending a mutation to /172.17.0.3"
+    );
+    press(&mut browser, "N");
+    assert_eq!(selected_activity(&browser), "Mutation handling is done");
+
+    press(&mut browser, "\t");
+    assert_eq!(selected_activity(&browser), "Processing a statement");
+    assert_eq!(
+        browser.selected_event().unwrap().source.to_string(),
+        "172.17.0.2"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn zoom() -> Result<(), Box<dyn std::error::Error>> {
+    let session = session()?;
+    let mut browser = Browser::new(&session, DurationFormat::default());
+    assert_eq!(browser.window(), (0, 1633));
+
+    press(&mut browser, "z");
+    assert_eq!(browser.window(), (0, 118));
+
+    // The last event starts 1289µs in, and stays put as we zoom in and out around it
+    press(&mut browser, "0G+");
+    assert_eq!(browser.window(), (644, 1461));
+    press(&mut browser, "-");
+    assert_eq!(browser.window(), (0, 1633));
+
+    Ok(())
+}
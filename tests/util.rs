@@ -1,3 +1,7 @@
+use seella::{
+    session_from_csv, sessions_from_csv_options, CsvSessionsOptions, EventsPath, Session,
+    SessionsPath,
+};
 use std::path::PathBuf;
 
 pub fn test_data(filename: &str) -> PathBuf {
@@ -7,3 +11,40 @@ pub fn test_data(filename: &str) -> PathBuf {
         filename
     ))
 }
+
+/// The single-partition read that most tests are run against.
+#[allow(dead_code)]
+pub fn session() -> Result<Session, Box<dyn std::error::Error>> {
+    session_from_csv(
+        &test_data("sessions.csv"),
+        &test_data("events.csv"),
+        "74ff67c0-397b-11ee-8ca4-9688db6cc0f1",
+    )
+}
+
+/// A range scan, which reads from several replicas at once.
+#[allow(dead_code)]
+pub fn range_scan() -> Result<Session, Box<dyn std::error::Error>> {
+    session_from_csv(
+        &test_data("range_sessions.csv"),
+        &test_data("events.csv"),
+        "75007930-397b-11ee-8ca4-9688db6cc0f1",
+    )
+}
+
+/// The options for reading the CSVs of many traces of a handful of queries.
+#[allow(dead_code)]
+pub fn many_sessions_options() -> CsvSessionsOptions {
+    CsvSessionsOptions {
+        sessions_path: SessionsPath(test_data("many_sessions.csv")),
+        events_path: EventsPath(test_data("many_events.csv")),
+        ..Default::default()
+    }
+}
+
+/// Every session in the CSVs of many traces of a handful of queries.
+#[allow(dead_code)]
+pub fn many_sessions() -> Result<Vec<Session>, Box<dyn std::error::Error>> {
+    let (sessions, _) = sessions_from_csv_options(&many_sessions_options())?;
+    Ok(sessions)
+}